@@ -0,0 +1,20 @@
+use xdg_desktop_entries::list_installed_applications;
+
+fn main() {
+    let applications = match list_installed_applications() {
+        Ok(applications) => applications,
+        Err(error) => {
+            eprintln!("Failed to list applications: {error:?}");
+            std::process::exit(1);
+        }
+    };
+
+    for application in applications {
+        println!(
+            "{}\t{}\t{}",
+            application.id,
+            application.name,
+            application.exec.unwrap_or_default()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    ApplicationDesktopEntry, Error, RawDesktopEntry, Result, localized_values, string_value,
+};
+
+/// An additional action of an application, declared in `Actions` and defined in a
+/// `[Desktop Action <id>]` group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(unused)]
+pub struct DesktopAction {
+    /// Everything after `Desktop Action ` in the group name, spaces included. It matches an
+    /// entry of `Actions` exactly.
+    pub id: String,
+    pub name: String,
+    pub name_localized: BTreeMap<String, String>,
+    pub icon: Option<String>,
+    pub exec: Option<String>,
+}
+
+impl DesktopAction {
+    fn from_group(id: &str, group: &HashMap<String, String>) -> Result<Self> {
+        if group.is_empty() {
+            return Err(Error::FormatError(format!(
+                "Group [Desktop Action {id}] is empty"
+            )));
+        }
+
+        Ok(DesktopAction {
+            id: id.to_string(),
+            name: group
+                .get("Name")
+                .ok_or(Error::FormatError(format!(
+                    "Missing required key 'Name' in action '{id}'"
+                )))?
+                .to_string(),
+            name_localized: localized_values(group, "Name"),
+            icon: string_value(group, "Icon"),
+            exec: string_value(group, "Exec"),
+        })
+    }
+}
+
+/// Parses the action groups referenced by the `Actions` key of `entry`. Groups that aren't
+/// referenced are ignored, as the spec requires.
+pub(crate) fn parse_actions(
+    raw: &RawDesktopEntry,
+    entry: &ApplicationDesktopEntry,
+) -> Result<Vec<DesktopAction>> {
+    let Some(actions) = &entry.actions else {
+        return Ok(Vec::new());
+    };
+
+    actions
+        .iter()
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            let group = raw
+                .get(&format!("Desktop Action {id}"))
+                .ok_or_else(|| missing_group_error(raw, id))?;
+            DesktopAction::from_group(id, group)
+        })
+        .collect()
+}
+
+/// Action IDs are case-sensitive, so a group that only differs in case doesn't match, but is
+/// most likely what was meant.
+fn missing_group_error(raw: &RawDesktopEntry, id: &str) -> Error {
+    let similar = raw.keys().find(|name| {
+        name.strip_prefix("Desktop Action ")
+            .is_some_and(|other| other.trim().eq_ignore_ascii_case(id))
+    });
+
+    match similar {
+        Some(name) => Error::FormatError(format!(
+            "Missing group [Desktop Action {id}], did you mean [{name}]? Action IDs are case-sensitive"
+        )),
+        None => Error::FormatError(format!("Missing group [Desktop Action {id}]")),
+    }
+}
+
+impl ApplicationDesktopEntry {
+    /// The parsed actions in the order `Actions` lists them. The `actions` field holds the
+    /// declared IDs.
+    pub fn actions(&self) -> &[DesktopAction] {
+        &self.desktop_actions
+    }
+
+    pub fn has_action(&self, id: &str) -> bool {
+        self.action(id).is_some()
+    }
+
+    pub fn action(&self, id: &str) -> Option<&DesktopAction> {
+        self.desktop_actions.iter().find(|action| action.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ApplicationDesktopEntry;
+
+    #[test]
+    fn action_id_with_spaces() {
+        let entry: ApplicationDesktopEntry = "[Desktop Entry]\nType=Application\nName=App\n\
+            Actions=Foo Bar;\n\n[Desktop Action Foo Bar]\nName=Foo\nExec=app --foo\n"
+            .try_into()
+            .unwrap();
+        assert!(entry.has_action("Foo Bar"));
+        assert_eq!(
+            entry.action("Foo Bar").unwrap().exec.as_deref(),
+            Some("app --foo")
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use crate::{ApplicationDesktopEntry, DesktopAction, Error, Result, Severity, validate};
+
+/// Builds an [`ApplicationDesktopEntry`] step by step. Like the fields of the entry, values are
+/// taken as they appear in a file, so they must already be escaped: pass arbitrary text
+/// through [`crate::escape_value`] first. See [`crate::DesktopEntryType::to_desktop_string`]
+/// for how unescaped values are written.
+#[derive(Debug, Clone, Default)]
+#[allow(unused)]
+pub struct ApplicationDesktopEntryBuilder {
+    entry: ApplicationDesktopEntry,
+}
+
+/// Sets up a [`DesktopAction`] for [`ApplicationDesktopEntryBuilder::action`].
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct DesktopActionBuilder {
+    action: DesktopAction,
+}
+
+impl DesktopActionBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.action.name = name.into();
+        self
+    }
+
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.action.icon = Some(icon.into());
+        self
+    }
+
+    pub fn exec(mut self, exec: impl Into<String>) -> Self {
+        self.action.exec = Some(exec.into());
+        self
+    }
+}
+
+macro_rules! string_setters {
+    ($($field:ident),*) => {$(
+        pub fn $field(mut self, value: impl Into<String>) -> Self {
+            self.entry.$field = Some(value.into());
+            self
+        }
+    )*};
+}
+
+macro_rules! list_setters {
+    ($($field:ident),*) => {$(
+        pub fn $field<I: IntoIterator<Item = S>, S: Into<String>>(mut self, values: I) -> Self {
+            self.entry.$field = Some(values.into_iter().map(Into::into).collect());
+            self
+        }
+    )*};
+}
+
+macro_rules! bool_setters {
+    ($($field:ident),*) => {$(
+        pub fn $field(mut self, value: bool) -> Self {
+            self.entry.$field = Some(value);
+            self
+        }
+    )*};
+}
+
+impl ApplicationDesktopEntry {
+    pub fn builder(name: impl Into<String>) -> ApplicationDesktopEntryBuilder {
+        ApplicationDesktopEntryBuilder::default().name(name)
+    }
+}
+
+impl ApplicationDesktopEntryBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.entry.name = name.into();
+        self
+    }
+
+    string_setters!(
+        version,
+        generic_name,
+        comment,
+        icon,
+        try_exec,
+        exec,
+        path,
+        startup_wm_class
+    );
+    list_setters!(
+        only_show_in,
+        not_show_in,
+        actions,
+        mime_type,
+        categories,
+        keywords
+    );
+    bool_setters!(
+        no_display,
+        hidden,
+        terminal,
+        startup_notify,
+        prefers_non_default_gpu,
+        single_main_window
+    );
+
+    /// Adds the action `id`, set up by `build`, to `Actions` and as a `[Desktop Action <id>]`
+    /// group. Adding an ID again replaces the earlier action.
+    pub fn action(
+        mut self,
+        id: impl Into<String>,
+        build: impl FnOnce(DesktopActionBuilder) -> DesktopActionBuilder,
+    ) -> Self {
+        let id = id.into();
+        let builder = DesktopActionBuilder {
+            action: DesktopAction {
+                id: id.clone(),
+                name: String::new(),
+                name_localized: BTreeMap::new(),
+                icon: None,
+                exec: None,
+            },
+        };
+        let action = build(builder).action;
+
+        let actions = self.entry.actions.get_or_insert_with(Vec::new);
+        if !actions.contains(&id) {
+            actions.push(id.clone());
+        }
+        match self
+            .entry
+            .desktop_actions
+            .iter_mut()
+            .find(|existing| existing.id == id)
+        {
+            Some(existing) => *existing = action,
+            None => self.entry.desktop_actions.push(action),
+        }
+        self
+    }
+
+    pub fn extension(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.entry.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    /// The entry as it was set up, without checking it.
+    pub fn build(self) -> ApplicationDesktopEntry {
+        self.entry
+    }
+
+    /// The entry, after checking it with [`validate`]. Fails with the first error found, or
+    /// when `name` is empty.
+    pub fn try_build(self) -> Result<ApplicationDesktopEntry> {
+        if self.entry.name.trim().is_empty() {
+            return Err(Error::FormatError("Name must not be empty".to_string()));
+        }
+
+        let issues = validate(&self.entry.to_raw());
+        if let Some(issue) = issues
+            .into_iter()
+            .find(|issue| issue.severity == Severity::Error)
+        {
+            return Err(Error::FormatError(issue.message));
+        }
+
+        Ok(self.entry)
+    }
+}
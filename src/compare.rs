@@ -0,0 +1,276 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::{
+    ApplicationDesktopEntry, DirectoryDesktopEntry, LinkDesktopEntry, RawDesktopEntry, Result,
+    parse_desktop_entry_raw, parse_exec,
+};
+
+/// A key whose value differs between two files, as found by [`diff_files`]. A side is `None`
+/// when the key, or its whole group, is missing from that file.
+///
+/// A group that only one of the files has is reported on its own as well, with `key` set to
+/// `None` and an empty string on the side that has the group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(unused)]
+pub struct KeyDiff {
+    pub group: String,
+    pub key: Option<String>,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// The keys that differ between the files at `left` and `right`, sorted by group and key.
+/// Values are compared as they appear in the files. A group that only one file has shows up
+/// as a group-level [`KeyDiff`] followed by all of its keys.
+pub fn diff_files(left: &Path, right: &Path) -> Result<Vec<KeyDiff>> {
+    Ok(diff_raw(
+        &parse_desktop_entry_raw(left)?,
+        &parse_desktop_entry_raw(right)?,
+    ))
+}
+
+fn diff_raw(left: &RawDesktopEntry, right: &RawDesktopEntry) -> Vec<KeyDiff> {
+    let groups: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    let mut diffs = Vec::new();
+
+    for group in groups {
+        let left_group = left.get(group);
+        let right_group = right.get(group);
+        if left_group.is_none() || right_group.is_none() {
+            diffs.push(KeyDiff {
+                group: group.clone(),
+                key: None,
+                left: left_group.map(|_| String::new()),
+                right: right_group.map(|_| String::new()),
+            });
+        }
+        let keys: BTreeSet<&String> = left_group
+            .into_iter()
+            .chain(right_group)
+            .flat_map(|group| group.keys())
+            .collect();
+
+        for key in keys {
+            let left_value = left_group.and_then(|group| group.get(key));
+            let right_value = right_group.and_then(|group| group.get(key));
+            if left_value != right_value {
+                diffs.push(KeyDiff {
+                    group: group.clone(),
+                    key: Some(key.clone()),
+                    left: left_value.cloned(),
+                    right: right_value.cloned(),
+                });
+            }
+        }
+    }
+
+    diffs
+}
+
+/// The arguments of `exec`, without the deprecated field codes that launchers drop anyway.
+fn normalized_exec(exec: &str) -> Option<Vec<String>> {
+    let mut args = parse_exec(exec).ok()?;
+    args.retain(|arg| !matches!(arg.as_str(), "%d" | "%D" | "%n" | "%N" | "%v" | "%m"));
+    Some(args)
+}
+
+fn sort_list(list: &mut Option<Vec<String>>) {
+    if let Some(list) = list {
+        list.sort();
+        list.dedup();
+    }
+}
+
+/// The raw groups without the keys in `ignore`, matching translations like `Name[de]` by their
+/// base key.
+fn without_keys(mut raw: RawDesktopEntry, ignore: &[&str]) -> RawDesktopEntry {
+    for group in raw.values_mut() {
+        group.retain(|key, _| {
+            let base = key.split_once('[').map_or(key.as_str(), |(base, _)| base);
+            !ignore.contains(&base)
+        });
+    }
+    raw
+}
+
+macro_rules! impl_eq_ignoring {
+    ($($entry:ty),*) => {$(
+        impl $entry {
+            /// Compares the entries as they would be written, leaving out the keys in `ignore`,
+            /// e.g. `&["Version"]`.
+            pub fn eq_ignoring(&self, other: &Self, ignore: &[&str]) -> bool {
+                without_keys(self.to_raw(), ignore) == without_keys(other.to_raw(), ignore)
+            }
+        }
+    )*};
+}
+
+impl_eq_ignoring!(
+    ApplicationDesktopEntry,
+    LinkDesktopEntry,
+    DirectoryDesktopEntry
+);
+
+impl ApplicationDesktopEntry {
+    fn with_sorted_lists(&self) -> Self {
+        let mut entry = self.clone();
+        sort_list(&mut entry.only_show_in);
+        sort_list(&mut entry.not_show_in);
+        sort_list(&mut entry.actions);
+        sort_list(&mut entry.mime_type);
+        sort_list(&mut entry.categories);
+        sort_list(&mut entry.keywords);
+        entry
+            .desktop_actions
+            .sort_by(|left, right| left.id.cmp(&right.id));
+        entry
+    }
+
+    /// Like `==`, but list fields such as `Categories` compare as sets, ignoring the order
+    /// their items are listed in.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.with_sorted_lists() == other.with_sorted_lists()
+    }
+
+    /// A copy without vendor `extensions`, so that comparisons only consider the keys the
+    /// spec defines.
+    pub fn normalized(&self) -> Self {
+        let mut entry = self.clone();
+        entry.extensions.clear();
+        entry
+    }
+
+    /// Whether `Exec` runs the same command as `other`, so that `firefox "%u"` equals
+    /// `firefox %u`. The values are compared by their arguments, with the deprecated field
+    /// codes left out; values that fail to parse compare as they are written. An entry without
+    /// `Exec` equals nothing.
+    pub fn exec_eq(&self, other: &str) -> bool {
+        let Some(exec) = &self.exec else {
+            return false;
+        };
+        match (normalized_exec(exec), normalized_exec(other)) {
+            (Some(args), Some(other_args)) => args == other_args,
+            _ => exec == other,
+        }
+    }
+}
+
+impl LinkDesktopEntry {
+    fn with_sorted_lists(&self) -> Self {
+        let mut entry = self.clone();
+        sort_list(&mut entry.only_show_in);
+        sort_list(&mut entry.not_show_in);
+        entry
+    }
+
+    /// Like `==`, but list fields such as `OnlyShowIn` compare as sets, ignoring the order
+    /// their items are listed in.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.with_sorted_lists() == other.with_sorted_lists()
+    }
+
+    /// A copy without vendor `extensions`, so that comparisons only consider the keys the
+    /// spec defines.
+    pub fn normalized(&self) -> Self {
+        let mut entry = self.clone();
+        entry.extensions.clear();
+        entry
+    }
+}
+
+impl DirectoryDesktopEntry {
+    fn with_sorted_lists(&self) -> Self {
+        let mut entry = self.clone();
+        sort_list(&mut entry.only_show_in);
+        sort_list(&mut entry.not_show_in);
+        entry
+    }
+
+    /// Like `==`, but list fields such as `OnlyShowIn` compare as sets, ignoring the order
+    /// their items are listed in.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.with_sorted_lists() == other.with_sorted_lists()
+    }
+
+    /// A copy without vendor `extensions`, so that comparisons only consider the keys the
+    /// spec defines.
+    pub fn normalized(&self) -> Self {
+        let mut entry = self.clone();
+        entry.extensions.clear();
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn diff(left: &str, right: &str) -> Vec<KeyDiff> {
+        let dir = TempDir::new();
+        let left = dir.write("left.desktop", left);
+        let right = dir.write("right.desktop", right);
+        diff_files(&left, &right).unwrap()
+    }
+
+    fn key_diff(
+        group: &str,
+        key: Option<&str>,
+        left: Option<&str>,
+        right: Option<&str>,
+    ) -> KeyDiff {
+        KeyDiff {
+            group: group.to_string(),
+            key: key.map(str::to_string),
+            left: left.map(str::to_string),
+            right: right.map(str::to_string),
+        }
+    }
+
+    const ENTRY: &str = "[Desktop Entry]\nType=Application\nName=App\n";
+
+    #[test]
+    fn changed_added_and_removed_keys() {
+        let diffs = diff(
+            &format!("{ENTRY}Exec=old\nIcon=app\n"),
+            &format!("{ENTRY}Exec=new\nComment=Hi\n"),
+        );
+        assert_eq!(
+            diffs,
+            [
+                key_diff("Desktop Entry", Some("Comment"), None, Some("Hi")),
+                key_diff("Desktop Entry", Some("Exec"), Some("old"), Some("new")),
+                key_diff("Desktop Entry", Some("Icon"), Some("app"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_groups_that_only_one_file_has_are_reported() {
+        assert_eq!(
+            diff(ENTRY, &format!("{ENTRY}[New]\n")),
+            [key_diff("New", None, None, Some(""))]
+        );
+        assert_eq!(
+            diff(&format!("{ENTRY}[Old]\n"), ENTRY),
+            [key_diff("Old", None, Some(""), None)]
+        );
+    }
+
+    #[test]
+    fn groups_that_only_one_file_has_come_with_their_keys() {
+        assert_eq!(
+            diff(ENTRY, &format!("{ENTRY}[Desktop Action new]\nName=New\n")),
+            [
+                key_diff("Desktop Action new", None, None, Some("")),
+                key_diff("Desktop Action new", Some("Name"), None, Some("New")),
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_files_have_no_diffs() {
+        assert!(diff(ENTRY, ENTRY).is_empty());
+    }
+}
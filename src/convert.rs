@@ -0,0 +1,40 @@
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry, LinkDesktopEntry};
+
+// Conversions between the entry types copy the keys all of them share, such as `Name`, `Icon`
+// and `Comment`, along with the extension keys. Keys specific to the source type are dropped,
+// and those specific to the target type are left unset.
+macro_rules! impl_common_conversion {
+    ($($from:ty => $to:ty),*) => {$(
+        impl From<$from> for $to {
+            // Directory entries only have the shared keys, leaving nothing to default.
+            #[allow(clippy::needless_update)]
+            fn from(entry: $from) -> Self {
+                Self {
+                    version: entry.version,
+                    name: entry.name,
+                    name_localized: entry.name_localized,
+                    generic_name: entry.generic_name,
+                    generic_name_localized: entry.generic_name_localized,
+                    no_display: entry.no_display,
+                    comment: entry.comment,
+                    comment_localized: entry.comment_localized,
+                    icon: entry.icon,
+                    hidden: entry.hidden,
+                    only_show_in: entry.only_show_in,
+                    not_show_in: entry.not_show_in,
+                    extensions: entry.extensions,
+                    ..Default::default()
+                }
+            }
+        }
+    )*};
+}
+
+impl_common_conversion!(
+    DirectoryDesktopEntry => ApplicationDesktopEntry,
+    LinkDesktopEntry => ApplicationDesktopEntry,
+    ApplicationDesktopEntry => LinkDesktopEntry,
+    DirectoryDesktopEntry => LinkDesktopEntry,
+    ApplicationDesktopEntry => DirectoryDesktopEntry,
+    LinkDesktopEntry => DirectoryDesktopEntry
+);
@@ -0,0 +1,41 @@
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry, LinkDesktopEntry};
+
+// Every boolean key of the spec defaults to `false` when it is absent.
+
+macro_rules! impl_common_bool_defaults {
+    ($($entry:ty),*) => {$(
+        impl $entry {
+            pub fn no_display_or_default(&self) -> bool {
+                self.no_display.unwrap_or(false)
+            }
+
+            pub fn hidden_or_default(&self) -> bool {
+                self.hidden.unwrap_or(false)
+            }
+        }
+    )*};
+}
+
+impl_common_bool_defaults!(
+    ApplicationDesktopEntry,
+    LinkDesktopEntry,
+    DirectoryDesktopEntry
+);
+
+impl ApplicationDesktopEntry {
+    pub fn terminal_or_default(&self) -> bool {
+        self.terminal.unwrap_or(false)
+    }
+
+    pub fn startup_notify_or_default(&self) -> bool {
+        self.startup_notify.unwrap_or(false)
+    }
+
+    pub fn prefers_non_default_gpu_or_default(&self) -> bool {
+        self.prefers_non_default_gpu.unwrap_or(false)
+    }
+
+    pub fn single_main_window_or_default(&self) -> bool {
+        self.single_main_window.unwrap_or(false)
+    }
+}
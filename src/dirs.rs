@@ -0,0 +1,53 @@
+use std::env;
+use std::path::PathBuf;
+
+/// `$XDG_DATA_HOME`, defaulting to `$HOME/.local/share`.
+pub fn data_home() -> PathBuf {
+    env_path("XDG_DATA_HOME").unwrap_or_else(|| home().join(".local/share"))
+}
+
+/// `$XDG_DATA_DIRS`, defaulting to `/usr/local/share:/usr/share`. Does not include
+/// [`data_home`].
+pub fn data_dirs() -> Vec<PathBuf> {
+    env_paths("XDG_DATA_DIRS")
+        .unwrap_or_else(|| vec!["/usr/local/share".into(), "/usr/share".into()])
+}
+
+/// `$XDG_CONFIG_HOME`, defaulting to `$HOME/.config`.
+pub fn config_home() -> PathBuf {
+    env_path("XDG_CONFIG_HOME").unwrap_or_else(|| home().join(".config"))
+}
+
+/// `$XDG_CONFIG_DIRS`, defaulting to `/etc/xdg`. Does not include [`config_home`].
+pub fn config_dirs() -> Vec<PathBuf> {
+    env_paths("XDG_CONFIG_DIRS").unwrap_or_else(|| vec!["/etc/xdg".into()])
+}
+
+/// The `applications` directories desktop entries are installed to, most important first:
+/// [`data_home`] followed by each of [`data_dirs`].
+pub fn applications_dirs() -> Vec<PathBuf> {
+    std::iter::once(data_home())
+        .chain(data_dirs())
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+fn home() -> PathBuf {
+    env::home_dir().unwrap_or_default()
+}
+
+// The spec requires relative paths in these variables to be ignored, and an empty variable to
+// be treated as unset.
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+fn env_paths(name: &str) -> Option<Vec<PathBuf>> {
+    let value = env::var_os(name)?;
+    let paths: Vec<PathBuf> = env::split_paths(&value)
+        .filter(|path| path.is_absolute())
+        .collect();
+    (!paths.is_empty()).then_some(paths)
+}
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ApplicationDesktopEntry, DesktopEntryType, Result, applications_dirs, current_desktops,
+    parse_desktop_entry,
+};
+
+/// A digest of an installed application, as needed to show it in a launcher.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(unused)]
+pub struct AppSummary {
+    /// The desktop file ID, e.g. `org.gnome.Nautilus.desktop`.
+    pub id: String,
+    /// The name localized for the environment's locale.
+    pub name: String,
+    pub icon: Option<String>,
+    pub exec: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct DiscoveryOptions {
+    /// The file extensions to consider, without the leading `.` and compared case-sensitively.
+    /// Other files, like READMEs or icons, are skipped without being read. Defaults to
+    /// `["desktop"]`.
+    pub extensions: Vec<String>,
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        DiscoveryOptions {
+            extensions: vec!["desktop".to_string()],
+        }
+    }
+}
+
+/// Looks up a single entry by its desktop file ID (e.g. `org.gnome.Nautilus`, with or without
+/// the `.desktop` suffix) in the [`applications_dirs`], returning the entry from the most
+/// important directory that provides it.
+///
+/// IDs of files in subdirectories use `-` in place of `/`, so `kde-konsole` also matches
+/// `kde/konsole.desktop`.
+pub fn find_entry_by_id(id: &str) -> Result<Option<DesktopEntryType>> {
+    find_entry_by_id_in(id, &applications_dirs())
+}
+
+/// Like [`find_entry_by_id`], searching `dirs` (most important first) instead.
+pub fn find_entry_by_id_in(id: &str, dirs: &[PathBuf]) -> Result<Option<DesktopEntryType>> {
+    let file_name = if id.ends_with(".desktop") {
+        id.to_string()
+    } else {
+        format!("{id}.desktop")
+    };
+
+    for dir in dirs {
+        if let Some(path) = find_desktop_file(dir, &file_name) {
+            return parse_desktop_entry(path).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// The path to install an entry to in `applications_dir`, named `<vendor>-<basename>.desktop`
+/// following the vendor prefix convention, or `<basename>.desktop` without a vendor. The
+/// `.desktop` suffix and the vendor prefix are only added when `basename` doesn't have them yet.
+pub fn install_path(applications_dir: &Path, vendor: Option<&str>, basename: &str) -> PathBuf {
+    let mut file_name = match vendor {
+        Some(vendor) if !basename.starts_with(&format!("{vendor}-")) => {
+            format!("{vendor}-{basename}")
+        }
+        _ => basename.to_string(),
+    };
+    if !file_name.ends_with(".desktop") {
+        file_name.push_str(".desktop");
+    }
+    applications_dir.join(file_name)
+}
+
+/// Every application entry in the [`applications_dirs`], with the path it was read from.
+pub fn discover_application_entries() -> Result<Vec<(PathBuf, ApplicationDesktopEntry)>> {
+    discover_application_entries_in(&applications_dirs())
+}
+
+/// Like [`discover_application_entries`], searching `dirs` (most important first) instead.
+///
+/// Only the first entry found for each desktop file ID is returned, and no visibility
+/// filtering is done. Files that fail to parse or aren't applications are skipped.
+pub fn discover_application_entries_in(
+    dirs: &[PathBuf],
+) -> Result<Vec<(PathBuf, ApplicationDesktopEntry)>> {
+    discover_application_entries_with(dirs, &DiscoveryOptions::default())
+}
+
+/// Like [`discover_application_entries_in`], with `options` selecting the files to read.
+pub fn discover_application_entries_with(
+    dirs: &[PathBuf],
+    options: &DiscoveryOptions,
+) -> Result<Vec<(PathBuf, ApplicationDesktopEntry)>> {
+    let mut entries = Vec::new();
+
+    for (_, path) in desktop_files_in(dirs, options)? {
+        if let Ok(DesktopEntryType::Application(entry)) = parse_desktop_entry(&path) {
+            entries.push((path, entry));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// The applications in the [`applications_dirs`] that are visible on the [`current_desktops`]
+/// and list `category` in `Categories`, as needed for a menu section such as `Graphics`.
+/// Categories are case-sensitive, as the spec defines them.
+pub fn entries_in_category(category: &str) -> Result<Vec<(PathBuf, ApplicationDesktopEntry)>> {
+    entries_in_category_in(category, &applications_dirs())
+}
+
+/// Like [`entries_in_category`], searching `dirs` (most important first) instead.
+pub fn entries_in_category_in(
+    category: &str,
+    dirs: &[PathBuf],
+) -> Result<Vec<(PathBuf, ApplicationDesktopEntry)>> {
+    let desktops = current_desktops();
+    let desktops: Vec<&str> = desktops.iter().map(String::as_str).collect();
+
+    let mut entries = discover_application_entries_in(dirs)?;
+    entries.retain(|(_, entry)| {
+        entry.is_visible(&desktops)
+            && entry
+                .categories
+                .iter()
+                .flatten()
+                .any(|listed| listed == category)
+    });
+    Ok(entries)
+}
+
+/// The applications installed in the [`applications_dirs`] that are visible on the
+/// [`current_desktops`], sorted by ID.
+pub fn list_installed_applications() -> Result<Vec<AppSummary>> {
+    list_installed_applications_in(&applications_dirs())
+}
+
+/// Like [`list_installed_applications`], searching `dirs` (most important first) instead.
+///
+/// An entry shadows entries with the same ID in later directories, even when it is hidden.
+/// Files that fail to parse are skipped.
+pub fn list_installed_applications_in(dirs: &[PathBuf]) -> Result<Vec<AppSummary>> {
+    list_installed_applications_with(dirs, &DiscoveryOptions::default())
+}
+
+/// Like [`list_installed_applications_in`], with `options` selecting the files to read.
+pub fn list_installed_applications_with(
+    dirs: &[PathBuf],
+    options: &DiscoveryOptions,
+) -> Result<Vec<AppSummary>> {
+    let desktops = current_desktops();
+    let desktops: Vec<&str> = desktops.iter().map(String::as_str).collect();
+    let mut applications = Vec::new();
+
+    for (id, path) in desktop_files_in(dirs, options)? {
+        let Ok(DesktopEntryType::Application(entry)) = parse_desktop_entry(&path) else {
+            continue;
+        };
+        if !entry.is_visible(&desktops) {
+            continue;
+        }
+
+        applications.push(AppSummary {
+            id,
+            name: entry.localized_name_for_env().to_string(),
+            icon: entry.icon,
+            exec: entry.exec,
+        });
+    }
+
+    Ok(applications)
+}
+
+/// Every file below `dirs` with one of the `options` extensions as `(id, path)`, keeping only
+/// the first file found for each ID. Directories that don't exist are skipped.
+pub(crate) fn desktop_files_in(
+    dirs: &[PathBuf],
+    options: &DiscoveryOptions,
+) -> Result<Vec<(String, PathBuf)>> {
+    let mut seen = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut files = Vec::new();
+
+    for dir in dirs {
+        collect_desktop_files(dir, "", options, &mut seen, &mut visited, &mut files)?;
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Identifies a directory independently of the path it was reached through.
+#[cfg(unix)]
+type DirId = (u64, u64);
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+#[cfg(unix)]
+fn dir_id(dir: &Path) -> std::io::Result<DirId> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = dir.metadata()?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_id(dir: &Path) -> std::io::Result<DirId> {
+    dir.canonicalize()
+}
+
+// Symlinks are followed, both to files and to directories. Each directory is only walked once,
+// so symlinks pointing back up the tree don't recurse forever.
+fn collect_desktop_files(
+    dir: &Path,
+    id_prefix: &str,
+    options: &DiscoveryOptions,
+    seen: &mut HashSet<String>,
+    visited: &mut HashSet<DirId>,
+    files: &mut Vec<(String, PathBuf)>,
+) -> Result<()> {
+    match dir_id(dir) {
+        Ok(id) if !visited.insert(id) => return Ok(()),
+        Ok(_) => (),
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error.into()),
+    }
+
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error.into()),
+    };
+
+    for dir_entry in entries {
+        let path = dir_entry?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let id = format!("{id_prefix}{file_name}");
+
+        if path.is_dir() {
+            collect_desktop_files(&path, &format!("{id}-"), options, seen, visited, files)?;
+        } else if has_extension(&path, &options.extensions) && seen.insert(id.clone()) {
+            files.push((id, path));
+        }
+    }
+
+    Ok(())
+}
+
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extensions.iter().any(|allowed| allowed == extension))
+}
+
+fn find_desktop_file(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    let path = dir.join(file_name);
+    if path.is_file() {
+        return Some(path);
+    }
+
+    file_name
+        .match_indices('-')
+        .map(|(index, _)| (&file_name[..index], &file_name[index + 1..]))
+        .filter(|(subdir, _)| dir.join(subdir).is_dir())
+        .find_map(|(subdir, rest)| find_desktop_file(&dir.join(subdir), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    const ENTRY: &str = "[Desktop Entry]\nType=Application\nName=a\nExec=a\n";
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_symlinks_without_looping() {
+        use std::os::unix::fs::symlink;
+
+        let dir = TempDir::new();
+        dir.write("applications/a.desktop", ENTRY);
+        dir.write("applications/kde/konsole.desktop", ENTRY);
+        let target = dir.write("elsewhere/real.desktop", ENTRY);
+        let applications = dir.path().join("applications");
+        symlink(&target, applications.join("linked.desktop")).unwrap();
+        symlink(&applications, applications.join("kde/loop")).unwrap();
+
+        let files = desktop_files_in(
+            std::slice::from_ref(&applications),
+            &DiscoveryOptions::default(),
+        )
+        .unwrap();
+        let ids: Vec<_> = files.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["a.desktop", "kde-konsole.desktop", "linked.desktop"]);
+        assert_eq!(files[2].1, applications.join("linked.desktop"));
+    }
+}
@@ -0,0 +1,364 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Result;
+use crate::escape::protect_whitespace;
+
+#[derive(Debug, Clone, Default)]
+#[allow(unused)]
+pub struct EditOptions {
+    /// Remove the header of a group once its last key is removed, instead of leaving the empty
+    /// group in place.
+    pub remove_empty_groups: bool,
+}
+
+/// What a line of the file holds, as far as editing is concerned.
+enum Line<'a> {
+    Header(&'a str),
+    Key(&'a str),
+    Other,
+}
+
+fn classify(line: &str) -> Line<'_> {
+    let trimmed = line.trim_end_matches(['\n', '\r']);
+    if trimmed.starts_with('[') && trimmed.ends_with(']') {
+        Line::Header(&trimmed[1..trimmed.len() - 1])
+    } else if trimmed.starts_with('#') {
+        Line::Other
+    } else {
+        match trimmed.split_once('=') {
+            Some((key, _)) => Line::Key(key.trim()),
+            None => Line::Other,
+        }
+    }
+}
+
+/// Replaces the file at `path` with `contents` without ever leaving a partly written file
+/// behind: the contents go to a temporary file in the same directory, which is synced to disk
+/// and then renamed over `path`. An existing file keeps its permissions, and a symlink is
+/// followed so that its target is replaced rather than the link.
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    // Distinguishes the temporary files of concurrent writes from the same process.
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path doesn't name a file"))?;
+    let temp_name = format!(
+        ".{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let temp_path = dir.join(temp_name);
+
+    if let Err(error) = write_and_rename(&temp_path, &path, contents) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error);
+    }
+
+    // Makes the rename itself durable. Not every filesystem supports syncing a directory, and
+    // the new contents are in place either way, so a failure here is ignored.
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+fn write_and_rename(temp_path: &Path, path: &Path, contents: &str) -> io::Result<()> {
+    let mut file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(temp_path, path)
+}
+
+/// Sets `key` in `group` of the file at `path` to `value`, given as it is to appear in the
+/// file. Everything else in the file, comments and order included, is kept as it is. A key
+/// that doesn't exist yet is added after the last key of the group, and a missing group is
+/// appended to the file. The file is replaced atomically, as
+/// [`crate::DesktopEntryType::write_to_file`] describes.
+pub fn set_key_in_file<P: AsRef<Path>>(path: P, group: &str, key: &str, value: &str) -> Result<()> {
+    let content = fs::read_to_string(&path)?;
+    write_atomically(path.as_ref(), &set_key(&content, group, key, value))?;
+    Ok(())
+}
+
+/// Removes `key` from `group` of the file at `path`, keeping everything else as it is.
+/// Removing a key that doesn't exist succeeds without changing the file. Like
+/// [`set_key_in_file`], the file is replaced atomically.
+pub fn remove_key_from_file<P: AsRef<Path>>(path: P, group: &str, key: &str) -> Result<()> {
+    remove_key_from_file_with(path, group, key, &EditOptions::default())
+}
+
+/// [`remove_key_from_file`] with `options`.
+pub fn remove_key_from_file_with<P: AsRef<Path>>(
+    path: P,
+    group: &str,
+    key: &str,
+    options: &EditOptions,
+) -> Result<()> {
+    let content = fs::read_to_string(&path)?;
+    let edited = remove_key(&content, group, key, options);
+    if edited != content {
+        write_atomically(path.as_ref(), &edited)?;
+    }
+    Ok(())
+}
+
+fn set_key(content: &str, group: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{key}={}\n", protect_whitespace(value));
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut in_group = false;
+    let mut insert_at = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        match classify(line) {
+            Line::Header(name) => in_group = name == group,
+            Line::Key(name) if in_group => {
+                if name == key {
+                    let mut edited = lines.clone();
+                    edited[index] = &new_line;
+                    return edited.concat();
+                }
+                insert_at = Some(index + 1);
+            }
+            _ => (),
+        }
+        if in_group && insert_at.is_none() {
+            insert_at = Some(index + 1);
+        }
+    }
+
+    let mut edited = String::with_capacity(content.len() + new_line.len());
+    match insert_at {
+        Some(index) => {
+            edited.push_str(&lines[..index].concat());
+            if !edited.is_empty() && !edited.ends_with('\n') {
+                edited.push('\n');
+            }
+            edited.push_str(&new_line);
+            edited.push_str(&lines[index..].concat());
+        }
+        None => {
+            edited.push_str(content);
+            if !edited.is_empty() {
+                if !edited.ends_with('\n') {
+                    edited.push('\n');
+                }
+                edited.push('\n');
+            }
+            edited.push_str(&format!("[{group}]\n"));
+            edited.push_str(&new_line);
+        }
+    }
+    edited
+}
+
+fn remove_key(content: &str, group: &str, key: &str, options: &EditOptions) -> String {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut keep = vec![true; lines.len()];
+    let mut in_group = false;
+    let mut header = None;
+    let mut removed_any = false;
+    let mut keys_left = 0;
+
+    for (index, line) in lines.iter().enumerate() {
+        match classify(line) {
+            Line::Header(name) => {
+                in_group = name == group;
+                if in_group {
+                    header = Some(index);
+                }
+            }
+            Line::Key(name) if in_group => {
+                if name == key {
+                    keep[index] = false;
+                    removed_any = true;
+                } else {
+                    keys_left += 1;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    if options.remove_empty_groups
+        && removed_any
+        && keys_left == 0
+        && let Some(header) = header
+    {
+        keep[header] = false;
+    }
+
+    lines
+        .iter()
+        .zip(keep)
+        .filter_map(|(line, keep)| keep.then_some(*line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    const CONTENT: &str = "# Written by hand\n[Desktop Entry]\nType=Application\n# The name\n\
+        Name=App\nExec=app\n\n[Desktop Action new]\nName=New\n";
+
+    #[test]
+    fn removing_a_missing_key_leaves_the_file_untouched() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", CONTENT);
+        let before = fs::metadata(&path).unwrap();
+
+        remove_key_from_file(&path, "Desktop Entry", "Icon").unwrap();
+        remove_key_from_file(&path, "Missing", "Name").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), CONTENT);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(fs::metadata(&path).unwrap().ino(), before.ino());
+        }
+        assert_eq!(
+            fs::metadata(&path).unwrap().modified().unwrap(),
+            before.modified().unwrap()
+        );
+    }
+
+    #[test]
+    fn removing_keeps_comments_and_order() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", CONTENT);
+
+        remove_key_from_file(&path, "Desktop Entry", "Type").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# Written by hand\n[Desktop Entry]\n# The name\nName=App\nExec=app\n\n\
+            [Desktop Action new]\nName=New\n"
+        );
+    }
+
+    #[test]
+    fn removing_the_last_key_keeps_the_header_by_default() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", CONTENT);
+
+        remove_key_from_file(&path, "Desktop Action new", "Name").unwrap();
+        let expected = CONTENT.strip_suffix("Name=New\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+    }
+
+    #[test]
+    fn removing_the_last_key_can_drop_the_header() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", CONTENT);
+        let options = EditOptions {
+            remove_empty_groups: true,
+        };
+
+        remove_key_from_file_with(&path, "Desktop Action new", "Name", &options).unwrap();
+        let expected = CONTENT
+            .strip_suffix("[Desktop Action new]\nName=New\n")
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+    }
+
+    #[test]
+    fn setting_an_existing_key_replaces_it_in_place() {
+        assert_eq!(
+            set_key(CONTENT, "Desktop Entry", "Name", "Other"),
+            CONTENT.replace("Name=App", "Name=Other")
+        );
+    }
+
+    #[test]
+    fn setting_a_new_key_appends_to_a_last_group_without_trailing_newline() {
+        let content = "[Desktop Entry]\nType=Application\nName=App";
+        assert_eq!(
+            set_key(content, "Desktop Entry", "Exec", "app"),
+            "[Desktop Entry]\nType=Application\nName=App\nExec=app\n"
+        );
+    }
+
+    #[test]
+    fn setting_a_key_in_a_missing_group_appends_the_group() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", CONTENT);
+
+        set_key_in_file(&path, "Desktop Action edit", "Name", "Edit").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{CONTENT}\n[Desktop Action edit]\nName=Edit\n")
+        );
+    }
+
+    fn temp_files_in(dir: &Path) -> Vec<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with('.') && name.ends_with(".tmp"))
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", CONTENT);
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        write_atomically(&path, "[Desktop Entry]\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[Desktop Entry]\n");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+        assert!(temp_files_in(dir.path()).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_replaces_the_symlink_target() {
+        let dir = TempDir::new();
+        let target = dir.write("real/app.desktop", CONTENT);
+        let link = dir.path().join("app.desktop");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomically(&link, "[Desktop Entry]\n").unwrap();
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&target).unwrap(), "[Desktop Entry]\n");
+        assert!(temp_files_in(dir.path()).is_empty());
+        assert!(temp_files_in(&dir.path().join("real")).is_empty());
+    }
+
+    #[test]
+    fn failed_atomic_write_leaves_no_temporary_file() {
+        let dir = TempDir::new();
+        dir.write("occupied/file", "");
+        let path = dir.path().join("occupied");
+
+        // A file can't be renamed over a directory.
+        assert!(write_atomically(&path, CONTENT).is_err());
+        assert!(path.is_dir());
+        assert!(temp_files_in(dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry, Error, LinkDesktopEntry, Result};
+
+/// Encodes a value for writing to a file: backslashes, newlines, carriage returns and tabs
+/// become `\\`, `\n`, `\r` and `\t`, and a space at either end becomes `\s` so it survives
+/// the trimming around `=`. The inverse of [`unescape_value`].
+pub fn escape_value(value: &str) -> String {
+    protect_whitespace(
+        &value
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\r', "\\r"),
+    )
+}
+
+// Encodes the characters that a line-based parse would lose, leaving any existing escape
+// sequences alone: line breaks, and whitespace at either end of the value, which the parser
+// trims. Tabs and carriage returns inside the value survive parsing and are kept as they are.
+pub(crate) fn protect_whitespace(value: &str) -> String {
+    let mut encoded = value.replace('\n', "\\n");
+
+    let escape = |c: char| match c {
+        ' ' => Some("\\s"),
+        '\t' => Some("\\t"),
+        '\r' => Some("\\r"),
+        _ => None,
+    };
+    if let Some(last) = encoded.chars().last()
+        && let Some(escaped) = escape(last)
+    {
+        encoded.pop();
+        encoded.push_str(escaped);
+    }
+    if let Some(first) = encoded.chars().next()
+        && let Some(escaped) = escape(first)
+    {
+        encoded.replace_range(..1, escaped);
+    }
+
+    encoded
+}
+
+/// Decodes the `\s`, `\n`, `\t`, `\r` and `\\` escape sequences of a value.
+///
+/// Returns a [`Error::FormatError`] on any other escape sequence, or when the value ends with
+/// a lone backslash.
+pub fn unescape_value(value: &str) -> Result<String> {
+    unescape_bytes(value.as_bytes()).map(utf8_string)
+}
+
+/// [`unescape_value`] on bytes, for values that needn't be valid UTF-8. The escape sequences
+/// are all ASCII, so the bytes in between are passed through untouched.
+pub(crate) fn unescape_bytes(value: &[u8]) -> Result<Vec<u8>> {
+    let mut unescaped = Vec::with_capacity(value.len());
+    let mut bytes = value.iter().copied().enumerate();
+
+    while let Some((index, byte)) = bytes.next() {
+        if byte != b'\\' {
+            unescaped.push(byte);
+            continue;
+        }
+
+        match bytes.next() {
+            Some((_, b's')) => unescaped.push(b' '),
+            Some((_, b'n')) => unescaped.push(b'\n'),
+            Some((_, b't')) => unescaped.push(b'\t'),
+            Some((_, b'r')) => unescaped.push(b'\r'),
+            Some((_, b'\\')) => unescaped.push(b'\\'),
+            Some(_) => {
+                let other = String::from_utf8_lossy(&value[index + 1..]);
+                return Err(Error::FormatError(format!(
+                    "Invalid escape sequence '\\{}'",
+                    other.chars().next().unwrap_or_default()
+                )));
+            }
+            None => {
+                return Err(Error::FormatError(
+                    "Value ends with an unfinished escape sequence".to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(unescaped)
+}
+
+/// Turns bytes taken apart or decoded at ASCII bytes back into the string they came from, which
+/// keeps them valid UTF-8.
+pub(crate) fn utf8_string(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).expect("ASCII edits keep UTF-8 valid")
+}
+
+/// Splits a `;`-separated list value, keeping `\;` as a literal `;` within an element, so
+/// `Foo\;Bar;Baz` gives `Foo;Bar` and `Baz`. An escaped backslash doesn't protect a following
+/// `;`: `C:\\;D` splits after the backslash. A single trailing empty element, from the
+/// customary trailing `;`, is dropped, while empty elements elsewhere are kept: `A;;B;` gives
+/// `A`, an empty element and `B`. Other escape sequences are kept as they are, see
+/// [`unescape_value`].
+pub fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(';') => current.push(';'),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => current.push('\\'),
+            },
+            ';' => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        items.push(current);
+    }
+    items
+}
+
+/// Joins list items into a `;`-separated value with a trailing `;`, escaping `;` within
+/// items as `\;`.
+pub fn join_list(items: &[impl AsRef<str>]) -> String {
+    items
+        .iter()
+        .map(|item| format!("{};", item.as_ref().replace(';', "\\;")))
+        .collect()
+}
+
+macro_rules! impl_decoded_accessors {
+    ($($entry:ty),*) => {$(
+        impl $entry {
+            /// `name` with escape sequences decoded. The field itself keeps the text as it
+            /// appears in the file.
+            pub fn name_decoded(&self) -> Result<String> {
+                unescape_value(&self.name)
+            }
+
+            /// `comment` with escape sequences decoded.
+            pub fn comment_decoded(&self) -> Result<Option<String>> {
+                self.comment.as_deref().map(unescape_value).transpose()
+            }
+        }
+    )*};
+}
+
+impl_decoded_accessors!(
+    ApplicationDesktopEntry,
+    LinkDesktopEntry,
+    DirectoryDesktopEntry
+);
+
+#[cfg(test)]
+mod tests {
+    use super::split_list;
+    use crate::ApplicationDesktopEntry;
+
+    #[test]
+    fn split_list_keeps_inner_empty_elements() {
+        assert_eq!(split_list("A;;B;"), ["A", "", "B"]);
+    }
+
+    #[test]
+    fn split_list_keeps_escaped_semicolons() {
+        assert_eq!(split_list(r"Foo\;Bar;Baz"), ["Foo;Bar", "Baz"]);
+    }
+
+    #[test]
+    fn categories_keep_escaped_semicolons() {
+        let entry: ApplicationDesktopEntry =
+            "[Desktop Entry]\nType=Application\nName=a\nCategories=Foo\\;Bar;Baz\n"
+                .try_into()
+                .unwrap();
+        assert_eq!(entry.categories.unwrap(), ["Foo;Bar", "Baz"]);
+    }
+}
@@ -0,0 +1,382 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::escape::{unescape_bytes, unescape_value, utf8_string};
+use crate::{ApplicationDesktopEntry, Error, Result, Warning};
+
+/// Options for building the command that launches an entry.
+#[derive(Debug, Clone, Default)]
+#[allow(unused)]
+pub struct LaunchOptions {
+    /// Expand `$VAR` and `${VAR}` in `Exec` and `Path`, which the spec doesn't allow but some
+    /// files rely on. Variables that aren't set expand to nothing, with a [`Warning`].
+    pub expand_env: bool,
+}
+
+/// Replaces `$NAME` and `${NAME}` with the value of the environment variable. A `$` that isn't
+/// followed by a variable name is kept.
+pub(crate) fn expand_env_vars(value: &str, warnings: &mut Vec<Warning>) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, consumed) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+
+        if name.is_empty() {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+
+        match env::var(name) {
+            Ok(value) => expanded.push_str(&value),
+            Err(_) => warnings.push(Warning::new(format!(
+                "Environment variable '{name}' is not set"
+            ))),
+        }
+        rest = &after[consumed..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Resolves a program the way a shell would: an absolute path is used as is, anything else is
+/// looked up in `$PATH`. Only executable files are returned.
+pub(crate) fn find_executable(program: &str) -> Option<PathBuf> {
+    let program = Path::new(program);
+    if program.is_absolute() {
+        return is_executable(program).then(|| program.to_path_buf());
+    }
+
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Splits an `Exec` value into its arguments, following the spec's quoting rules. The value
+/// is expected as it appears in the file: string escapes are decoded first, then double
+/// quotes group arguments, inside which `\"`, `` \` ``, `\$` and `\\` are escapes. Field codes
+/// are left untouched.
+pub fn parse_exec(exec: &str) -> Result<Vec<String>> {
+    parse_exec_bytes(exec.as_bytes()).map(|args| args.into_iter().map(utf8_string).collect())
+}
+
+/// [`parse_exec`] on bytes. The escape sequences, quotes and separators are all ASCII, so the
+/// bytes in between are passed through untouched, and valid UTF-8 stays valid.
+pub(crate) fn parse_exec_bytes(exec: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let exec = unescape_bytes(exec)?;
+    let mut args = Vec::new();
+    let mut current = Vec::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    let mut bytes = exec.into_iter();
+
+    while let Some(byte) = bytes.next() {
+        if in_quotes {
+            match byte {
+                b'"' => in_quotes = false,
+                b'\\' => match bytes.next() {
+                    Some(next @ (b'"' | b'`' | b'$' | b'\\')) => current.push(next),
+                    Some(next) => current.extend([b'\\', next]),
+                    None => current.push(b'\\'),
+                },
+                _ => current.push(byte),
+            }
+            continue;
+        }
+
+        match byte {
+            b' ' | b'\t' | b'\n' => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            b'"' => {
+                in_quotes = true;
+                in_arg = true;
+            }
+            _ => {
+                current.push(byte);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err(Error::FormatError("Unterminated quote in Exec".to_string()));
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// What the field codes of an `Exec` value expand to. `icon` and `name` are given as they
+/// appear in the file and decoded when used.
+pub(crate) struct FieldValues<'a> {
+    pub(crate) files: Vec<&'a [u8]>,
+    pub(crate) icon: Option<&'a [u8]>,
+    pub(crate) name: &'a [u8],
+}
+
+/// Expands the field codes in the arguments [`parse_exec_bytes`] returns, as
+/// [`ApplicationDesktopEntry::build_command`] describes.
+pub(crate) fn expand_field_codes(
+    args: Vec<Vec<u8>>,
+    values: &FieldValues<'_>,
+) -> Result<Vec<Vec<u8>>> {
+    let mut command = Vec::new();
+    for arg in args {
+        match arg.as_slice() {
+            b"%f" | b"%u" => command.extend(values.files.first().map(|file| file.to_vec())),
+            b"%F" | b"%U" => command.extend(values.files.iter().map(|file| file.to_vec())),
+            b"%i" => {
+                if let Some(icon) = values.icon {
+                    command.push(b"--icon".to_vec());
+                    command.push(unescape_bytes(icon)?);
+                }
+            }
+            b"%k" | b"%d" | b"%D" | b"%n" | b"%N" | b"%v" | b"%m" => (),
+            _ => command.push(expand_inline_field_codes(&arg, values)?),
+        }
+    }
+    Ok(command)
+}
+
+/// Expands the field codes within a single argument, where only the ones that expand to a
+/// single value may appear.
+fn expand_inline_field_codes(arg: &[u8], values: &FieldValues<'_>) -> Result<Vec<u8>> {
+    let mut expanded = Vec::with_capacity(arg.len());
+    let mut bytes = arg.iter().copied().enumerate();
+
+    while let Some((_, byte)) = bytes.next() {
+        if byte != b'%' {
+            expanded.push(byte);
+            continue;
+        }
+
+        match bytes.next() {
+            Some((_, b'%')) => expanded.push(b'%'),
+            Some((_, b'f' | b'u')) => {
+                expanded.extend_from_slice(values.files.first().copied().unwrap_or_default())
+            }
+            Some((_, b'c')) => expanded.extend(unescape_bytes(values.name)?),
+            Some((_, b'k' | b'd' | b'D' | b'n' | b'N' | b'v' | b'm')) => (),
+            Some((_, code @ (b'F' | b'U' | b'i'))) => {
+                return Err(Error::FormatError(format!(
+                    "Field code %{} must be a standalone argument",
+                    char::from(code)
+                )));
+            }
+            Some((index, _)) => {
+                let code = String::from_utf8_lossy(&arg[index..]);
+                return Err(Error::FormatError(format!(
+                    "Invalid field code %{} in Exec",
+                    code.chars().next().unwrap_or_default()
+                )));
+            }
+            None => {
+                return Err(Error::FormatError(
+                    "Exec ends with an unfinished field code".to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(expanded)
+}
+
+impl ApplicationDesktopEntry {
+    /// Expands `Exec` into the argument vector to launch the application with `files`. The
+    /// first element is the program and the rest are its arguments, exactly as they are to be
+    /// passed to [`std::process::Command`], without any further shell processing. Nothing is
+    /// executed; launching the command is up to the caller.
+    ///
+    /// `%f` and `%u` take the first of `files`, `%F` and `%U` take all of them, `%i` becomes
+    /// `--icon <Icon>` (or nothing when `Icon` isn't set), `%c` the localized name and `%%` a
+    /// literal `%`, with escape sequences in `Icon` and the name decoded. `%k` and the
+    /// deprecated field codes expand to nothing.
+    pub fn build_command(&self, files: &[String]) -> Result<Vec<String>> {
+        self.build_command_with(files, &LaunchOptions::default())
+            .map(|(command, _)| command)
+    }
+
+    /// [`Self::build_command`] with `options`, returning the warnings recorded along the way.
+    pub fn build_command_with(
+        &self,
+        files: &[String],
+        options: &LaunchOptions,
+    ) -> Result<(Vec<String>, Vec<Warning>)> {
+        let exec = self
+            .exec
+            .as_deref()
+            .ok_or(Error::FormatError("Entry has no Exec key".to_string()))?;
+
+        let mut warnings = Vec::new();
+        let mut args = parse_exec_bytes(exec.as_bytes())?;
+        if options.expand_env {
+            for arg in &mut args {
+                let expanded = expand_env_vars(&utf8_string(std::mem::take(arg)), &mut warnings);
+                *arg = expanded.into_bytes();
+            }
+        }
+
+        let values = FieldValues {
+            files: files.iter().map(|file| file.as_bytes()).collect(),
+            icon: self.icon.as_deref().map(str::as_bytes),
+            name: self.localized_name_for_env().as_bytes(),
+        };
+        let command = expand_field_codes(args, &values)?;
+        Ok((command.into_iter().map(utf8_string).collect(), warnings))
+    }
+
+    /// The decoded `Path` to launch the application in, with environment variables expanded
+    /// when `options` ask for it.
+    pub fn working_dir(&self, options: &LaunchOptions) -> Result<(Option<String>, Vec<Warning>)> {
+        let mut warnings = Vec::new();
+        let Some(path) = &self.path else {
+            return Ok((None, warnings));
+        };
+
+        let path = unescape_value(path)?;
+        let path = if options.expand_env {
+            expand_env_vars(&path, &mut warnings)
+        } else {
+            path
+        };
+        Ok((Some(path), warnings))
+    }
+
+    /// The program `Exec` runs, resolved like [`std::process::Command`] would: an absolute path
+    /// as it is, anything else through `$PATH`. `None` when `Exec` is missing or doesn't parse,
+    /// or when no executable file is found, which is what launchers report as a missing binary.
+    pub fn resolved_exec_binary(&self) -> Option<PathBuf> {
+        let program = parse_exec(self.exec.as_deref()?).ok()?.into_iter().next()?;
+        find_executable(&program)
+    }
+
+    /// [`Self::build_command`], prefixed with `terminal` (e.g. `["xterm", "-e"]`) when the entry
+    /// sets `Terminal=true`. The crate doesn't pick a terminal emulator for you; which one to
+    /// use is up to the caller.
+    pub fn terminal_command(&self, terminal: &[String], files: &[String]) -> Result<Vec<String>> {
+        self.terminal_command_with(terminal, files, &LaunchOptions::default())
+            .map(|(command, _)| command)
+    }
+
+    /// [`Self::terminal_command`] with `options`, returning the warnings recorded along the way.
+    pub fn terminal_command_with(
+        &self,
+        terminal: &[String],
+        files: &[String],
+        options: &LaunchOptions,
+    ) -> Result<(Vec<String>, Vec<Warning>)> {
+        let (command, warnings) = self.build_command_with(files, options)?;
+        if self.terminal != Some(true) {
+            return Ok((command, warnings));
+        }
+        Ok((terminal.iter().cloned().chain(command).collect(), warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ApplicationDesktopEntry;
+
+    #[test]
+    fn icon_code_without_icon_expands_to_nothing() {
+        let entry = ApplicationDesktopEntry::builder("App")
+            .exec("app %i --flag")
+            .build();
+        assert_eq!(entry.build_command(&[]).unwrap(), ["app", "--flag"]);
+    }
+
+    #[test]
+    fn icon_code_becomes_two_arguments() {
+        let entry = ApplicationDesktopEntry::builder("App")
+            .icon("app-icon")
+            .exec("app %i")
+            .build();
+        assert_eq!(
+            entry.build_command(&[]).unwrap(),
+            ["app", "--icon", "app-icon"]
+        );
+    }
+
+    #[test]
+    fn name_and_icon_are_decoded() {
+        let entry = ApplicationDesktopEntry::builder(r"A\sB")
+            .icon(r"my\sicon")
+            .exec("app %c %i")
+            .build();
+        assert_eq!(
+            entry.build_command(&[]).unwrap(),
+            ["app", "A B", "--icon", "my icon"]
+        );
+    }
+
+    fn entry_running(exec: &str) -> ApplicationDesktopEntry {
+        ApplicationDesktopEntry::builder("App").exec(exec).build()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolved_exec_binary() {
+        use std::os::unix::fs::PermissionsExt;
+
+        use crate::testing::{TempDir, with_env};
+
+        let dir = TempDir::new();
+        let binary = dir.write("bin/app", "#!/bin/sh\n");
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        dir.write("bin/not-executable", "");
+
+        let path = dir.path().join("bin");
+        with_env(&[("PATH", Some(path.as_os_str()))], || {
+            let absolute = format!("{} --flag", binary.display());
+            assert_eq!(
+                entry_running(&absolute).resolved_exec_binary(),
+                Some(binary.clone())
+            );
+            assert_eq!(
+                entry_running("app %f").resolved_exec_binary(),
+                Some(binary.clone())
+            );
+            assert_eq!(entry_running("missing").resolved_exec_binary(), None);
+            assert_eq!(entry_running("not-executable").resolved_exec_binary(), None);
+        });
+        assert_eq!(
+            ApplicationDesktopEntry::builder("App")
+                .build()
+                .resolved_exec_binary(),
+            None
+        );
+    }
+}
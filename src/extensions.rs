@@ -0,0 +1,36 @@
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry, LinkDesktopEntry};
+
+macro_rules! impl_extension_accessors {
+    ($($entry:ty),*) => {$(
+        impl $entry {
+            /// The value of the extension key `key`, e.g. `X-GNOME-UsesNotifications`.
+            pub fn extension(&self, key: &str) -> Option<&str> {
+                self.extensions.get(key).map(String::as_str)
+            }
+
+            /// The extension `key` as an integer, or `None` when it is missing or isn't one.
+            pub fn extension_int(&self, key: &str) -> Option<i64> {
+                self.extension(key)?.trim().parse().ok()
+            }
+
+            /// The extension `key` as a boolean. Vendors aren't bound to the spec's literals, so
+            /// besides `true` and `false` this accepts `1` and `0`, ignoring case.
+            pub fn extension_bool(&self, key: &str) -> Option<bool> {
+                let value = self.extension(key)?.trim();
+                if value.eq_ignore_ascii_case("true") || value == "1" {
+                    Some(true)
+                } else if value.eq_ignore_ascii_case("false") || value == "0" {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+        }
+    )*};
+}
+
+impl_extension_accessors!(
+    ApplicationDesktopEntry,
+    LinkDesktopEntry,
+    DirectoryDesktopEntry
+);
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry, LinkDesktopEntry};
+
+/// Extensions of the image formats icon themes are made of. Theme names may well contain dots,
+/// as in `org.gnome.Nautilus`, so only these count as a file extension.
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "svg", "svgz", "xpm"];
+
+/// Whether `icon` looks like a file meant to be loaded directly, but isn't given by an absolute
+/// path: such an icon is looked up as a theme name and never found.
+pub(crate) fn is_misplaced_icon_path(icon: &str) -> bool {
+    if Path::new(icon).is_absolute() {
+        return false;
+    }
+    icon.contains('/')
+        || Path::new(icon)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+macro_rules! impl_icon_kind {
+    ($($entry:ty),*) => {$(
+        impl $entry {
+            /// Whether `Icon` names a file by its absolute path, to be loaded as it is.
+            pub fn icon_is_path(&self) -> bool {
+                self.icon
+                    .as_deref()
+                    .is_some_and(|icon| Path::new(icon).is_absolute())
+            }
+
+            /// Whether `Icon` is set to a name to look up in the icon theme. This includes
+            /// broken names like `firefox.png`, which [`crate::validate`] warns about.
+            pub fn icon_is_theme_name(&self) -> bool {
+                self.icon.is_some() && !self.icon_is_path()
+            }
+        }
+    )*};
+}
+
+impl_icon_kind!(
+    ApplicationDesktopEntry,
+    LinkDesktopEntry,
+    DirectoryDesktopEntry
+);
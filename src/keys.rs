@@ -0,0 +1,127 @@
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry, LinkDesktopEntry};
+
+/// The keys every entry type models, in the order the spec lists them.
+pub const COMMON_KEYS: &[&str] = &[
+    "Version",
+    "Name",
+    "GenericName",
+    "NoDisplay",
+    "Comment",
+    "Icon",
+    "Hidden",
+    "OnlyShowIn",
+    "NotShowIn",
+];
+
+/// The keys modeled by [`ApplicationDesktopEntry`](crate::ApplicationDesktopEntry).
+pub const APPLICATION_KEYS: &[&str] = &[
+    "Version",
+    "Name",
+    "GenericName",
+    "NoDisplay",
+    "Comment",
+    "Icon",
+    "Hidden",
+    "OnlyShowIn",
+    "NotShowIn",
+    "TryExec",
+    "Exec",
+    "Path",
+    "Terminal",
+    "Actions",
+    "MimeType",
+    "Categories",
+    "Keywords",
+    "StartupNotify",
+    "StartupWMClass",
+    "PrefersNonDefaultGPU",
+    "SingleMainWindow",
+];
+
+/// The keys modeled by [`LinkDesktopEntry`](crate::LinkDesktopEntry).
+pub const LINK_KEYS: &[&str] = &[
+    "Version",
+    "Name",
+    "GenericName",
+    "NoDisplay",
+    "Comment",
+    "Icon",
+    "Hidden",
+    "OnlyShowIn",
+    "NotShowIn",
+    "URL",
+];
+
+/// The keys modeled by [`DirectoryDesktopEntry`](crate::DirectoryDesktopEntry).
+pub const DIRECTORY_KEYS: &[&str] = COMMON_KEYS;
+
+macro_rules! impl_keys_present {
+    ($($entry:ty => $keys:expr),*) => {$(
+        impl $entry {
+            /// The keys of the standard fields that are set, in the order the spec lists them.
+            /// `Name` is always included since it is required.
+            pub fn keys_present(&self) -> Vec<&'static str> {
+                let raw = self.to_raw();
+                let group = &raw["Desktop Entry"];
+                $keys
+                    .iter()
+                    .copied()
+                    .filter(|key| group.contains_key(*key))
+                    .collect()
+            }
+        }
+    )*};
+}
+
+impl_keys_present!(
+    ApplicationDesktopEntry => APPLICATION_KEYS,
+    LinkDesktopEntry => LINK_KEYS,
+    DirectoryDesktopEntry => DIRECTORY_KEYS
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_keys_lead_every_table() {
+        for keys in [APPLICATION_KEYS, LINK_KEYS, DIRECTORY_KEYS] {
+            assert_eq!(&keys[..COMMON_KEYS.len()], COMMON_KEYS);
+        }
+    }
+
+    #[test]
+    fn tables_cover_every_field() {
+        let common = "[Desktop Entry]\nVersion=1.5\nName=a\nGenericName=b\nNoDisplay=false\n\
+            Comment=c\nIcon=d\nHidden=false\nOnlyShowIn=A;\nNotShowIn=B;\n";
+
+        let application: ApplicationDesktopEntry = format!(
+            "{common}Type=Application\nTryExec=e\nExec=e\nPath=/\nTerminal=false\n\
+            Actions=\nMimeType=text/plain;\nCategories=Utility;\nKeywords=k;\n\
+            StartupNotify=true\nStartupWMClass=w\nPrefersNonDefaultGPU=false\n\
+            SingleMainWindow=true\n"
+        )
+        .as_str()
+        .try_into()
+        .unwrap();
+        assert_eq!(application.keys_present(), APPLICATION_KEYS);
+
+        let link: LinkDesktopEntry = format!("{common}Type=Link\nURL=https://example.org\n")
+            .as_str()
+            .try_into()
+            .unwrap();
+        assert_eq!(link.keys_present(), LINK_KEYS);
+
+        let directory: DirectoryDesktopEntry = format!("{common}Type=Directory\n")
+            .as_str()
+            .try_into()
+            .unwrap();
+        assert_eq!(directory.keys_present(), DIRECTORY_KEYS);
+    }
+
+    #[test]
+    fn unset_fields_are_left_out() {
+        let entry = ApplicationDesktopEntry::builder("a").exec("a").build();
+        assert_eq!(entry.keys_present(), ["Name", "Exec"]);
+    }
+}
@@ -1,121 +1,485 @@
-use std::collections::HashMap;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::result;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+mod action;
+mod builder;
+mod categories;
+mod compare;
+mod convert;
+mod defaults;
+mod dirs;
+mod discovery;
+mod edit;
+mod escape;
+mod exec;
+mod extensions;
+mod icon;
+mod keys;
+mod locale;
+mod mime;
+mod mimeapps;
+#[cfg(all(unix, feature = "os_strings"))]
+mod os_strings;
+mod partial;
+mod raw;
+mod search;
+mod serialize;
+mod stream;
+#[cfg(test)]
+mod testing;
+mod text;
+mod validation;
+mod version;
+mod visibility;
+
+pub use action::DesktopAction;
+pub use builder::{ApplicationDesktopEntryBuilder, DesktopActionBuilder};
+pub use categories::{ADDITIONAL_CATEGORIES, MAIN_CATEGORIES, RESERVED_CATEGORIES};
+pub use compare::{KeyDiff, diff_files};
+pub use dirs::{applications_dirs, config_dirs, config_home, data_dirs, data_home};
+pub use discovery::{
+    AppSummary, DiscoveryOptions, discover_application_entries, discover_application_entries_in,
+    discover_application_entries_with, entries_in_category, entries_in_category_in,
+    find_entry_by_id, find_entry_by_id_in, install_path, list_installed_applications,
+    list_installed_applications_in, list_installed_applications_with,
+};
+pub use edit::{EditOptions, remove_key_from_file, remove_key_from_file_with, set_key_in_file};
+pub use escape::{escape_value, join_list, split_list, unescape_value};
+pub use exec::{LaunchOptions, parse_exec};
+pub use keys::{APPLICATION_KEYS, COMMON_KEYS, DIRECTORY_KEYS, LINK_KEYS};
+pub use locale::preferred_locales;
+pub use mimeapps::{
+    mimeapps_list_paths, resolve_default_application, resolve_default_application_in,
+};
+#[cfg(all(unix, feature = "os_strings"))]
+pub use os_strings::{OsLaunchValues, parse_exec_os, parse_launch_values_os};
+pub use partial::{Diagnostic, parse_partial};
+pub use raw::{
+    GroupView, RawDesktopFile, group_from_pairs, parse_desktop_file_raw, raw_from_groups,
+};
+pub use serialize::{GroupOrder, SerializeOptions};
+pub use stream::parse_desktop_entries_stream;
+pub use validation::{
+    Severity, ValidationIssue, ValidationOptions, validate, validate_file, validate_str,
+    validate_with,
+};
+pub use version::SpecVersion;
+pub use visibility::current_desktops;
 
 pub type Result<T> = result::Result<T, Error>;
 pub type RawDesktopEntry = HashMap<String, HashMap<String, String>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(unused)]
-
 pub enum Error {
-    IoError(std::io::Error),
+    /// The underlying error is shared so that `Error` stays `Clone`.
+    IoError(Arc<std::io::Error>),
     FormatError(String),
 }
 
-#[derive(Debug, Clone)]
-#[allow(unused)]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IoError(error) => write!(f, "{error}"),
+            Error::FormatError(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IoError(error) => Some(error.as_ref()),
+            Error::FormatError(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::IoError(Arc::new(error))
+    }
+}
 
+/// The [`Default`] entry has an empty `name` and isn't valid until `name` is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[allow(unused)]
 pub struct ApplicationDesktopEntry {
     pub version: Option<String>,
     pub name: String,
+    /// Translations of `name`, keyed by the locale suffix of `Name[locale]`.
+    pub name_localized: BTreeMap<String, String>,
     pub generic_name: Option<String>,
+    /// Translations of `generic_name`, keyed like `name_localized`.
+    pub generic_name_localized: BTreeMap<String, String>,
     pub no_display: Option<bool>,
     pub comment: Option<String>,
+    /// Translations of `comment`, keyed like `name_localized`.
+    pub comment_localized: BTreeMap<String, String>,
     pub icon: Option<String>,
     pub hidden: Option<bool>,
-    pub only_show_in: Option<String>,
-    pub not_show_in: Option<String>,
+    pub only_show_in: Option<Vec<String>>,
+    pub not_show_in: Option<Vec<String>>,
     pub try_exec: Option<String>,
     pub exec: Option<String>,
     pub path: Option<String>,
     pub terminal: Option<bool>,
-    pub actions: Option<String>,
-    pub mime_type: Option<String>,
-    pub categories: Option<String>,
-    pub keywords: Option<String>,
+    pub actions: Option<Vec<String>>,
+    pub mime_type: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub keywords: Option<Vec<String>>,
+    /// Translations of `keywords`, keyed like `name_localized`.
+    pub keywords_localized: BTreeMap<String, Vec<String>>,
     pub startup_notify: Option<bool>,
     pub startup_wm_class: Option<String>,
     pub prefers_non_default_gpu: Option<bool>,
     pub single_main_window: Option<bool>,
+    /// The actions referenced by `actions`, parsed from their `[Desktop Action]` groups.
+    pub desktop_actions: Vec<DesktopAction>,
+    /// Vendor extension keys (`X-...`) of the main group, with their values as they appear in
+    /// the file.
+    pub extensions: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Clone)]
+/// The [`Default`] entry has an empty `name` and no `url`, and isn't valid until both are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 #[allow(unused)]
-
 pub struct LinkDesktopEntry {
     pub version: Option<String>,
     pub name: String,
+    /// Translations of `name`, keyed by the locale suffix of `Name[locale]`.
+    pub name_localized: BTreeMap<String, String>,
     pub generic_name: Option<String>,
+    /// Translations of `generic_name`, keyed like `name_localized`.
+    pub generic_name_localized: BTreeMap<String, String>,
     pub no_display: Option<bool>,
     pub comment: Option<String>,
+    /// Translations of `comment`, keyed like `name_localized`.
+    pub comment_localized: BTreeMap<String, String>,
     pub icon: Option<String>,
     pub hidden: Option<bool>,
-    pub only_show_in: Option<String>,
-    pub not_show_in: Option<String>,
-    pub url: String,
+    pub only_show_in: Option<Vec<String>>,
+    pub not_show_in: Option<Vec<String>>,
+    /// Required by the spec, only `None` for entries parsed with [`ParseOptions::lenient`].
+    pub url: Option<String>,
+    /// Vendor extension keys (`X-...`) of the main group, with their values as they appear in
+    /// the file.
+    pub extensions: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Clone)]
+/// The [`Default`] entry has an empty `name` and isn't valid until `name` is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 #[allow(unused)]
 pub struct DirectoryDesktopEntry {
     pub version: Option<String>,
     pub name: String,
+    /// Translations of `name`, keyed by the locale suffix of `Name[locale]`.
+    pub name_localized: BTreeMap<String, String>,
     pub generic_name: Option<String>,
+    /// Translations of `generic_name`, keyed like `name_localized`.
+    pub generic_name_localized: BTreeMap<String, String>,
     pub no_display: Option<bool>,
     pub comment: Option<String>,
+    /// Translations of `comment`, keyed like `name_localized`.
+    pub comment_localized: BTreeMap<String, String>,
     pub icon: Option<String>,
     pub hidden: Option<bool>,
-    pub only_show_in: Option<String>,
-    pub not_show_in: Option<String>,
+    pub only_show_in: Option<Vec<String>>,
+    pub not_show_in: Option<Vec<String>>,
+    /// Vendor extension keys (`X-...`) of the main group, with their values as they appear in
+    /// the file.
+    pub extensions: BTreeMap<String, String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[allow(unused)]
-
+#[allow(clippy::large_enum_variant)]
 pub enum DesktopEntryType {
     Application(ApplicationDesktopEntry),
     Link(LinkDesktopEntry),
     Directory(DirectoryDesktopEntry),
 }
 
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct ParseOptions {
+    /// Recover from common mistakes in real-world files instead of failing, recording a
+    /// [`Warning`] for each recovery.
+    pub lenient: bool,
+    /// Attribute keys that appear before any group header to an implicit `[Desktop Entry]`
+    /// group, for files that are only missing the header.
+    pub assume_desktop_entry_group: bool,
+    /// Trim whitespace around values (the default). When unset, values are kept verbatim so
+    /// that the raw map reproduces the file exactly. The typed entries keep string values
+    /// verbatim as well, but still ignore the whitespace around `Type`, booleans and lists.
+    pub trim_values: bool,
+    /// Accept `0` and `1` for boolean keys, as some generators write them, recording a
+    /// [`Warning`] for each. Off by default, where only `true` and `false` are booleans.
+    pub accept_numeric_bools: bool,
+    pub duplicate_key_policy: DuplicateKeyPolicy,
+}
+
+/// What to do with a key that appears more than once in a group, which the spec forbids.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(unused)]
+pub enum DuplicateKeyPolicy {
+    /// Fail to parse. This is the default of [`ParseOptions`]; the functions that take no
+    /// options, like [`parse_desktop_entry`] or `str::parse`, keep the last value instead.
+    #[default]
+    Error,
+    /// Keep the first value, recording a [`Warning`].
+    First,
+    /// Keep the last value, recording a [`Warning`].
+    Last,
+    /// Keep the last value in the map and every value in [`RawDesktopFile::values`].
+    CollectAll,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            lenient: false,
+            assume_desktop_entry_group: false,
+            trim_values: true,
+            accept_numeric_bools: false,
+            duplicate_key_policy: DuplicateKeyPolicy::Error,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The options of the functions that take none: the defaults, except that a repeated key
+    /// keeps its last value as it always has here.
+    pub(crate) fn keeping_last_value() -> Self {
+        ParseOptions {
+            duplicate_key_policy: DuplicateKeyPolicy::Last,
+            ..ParseOptions::default()
+        }
+    }
+}
+
+/// A problem the parser recovered from. The `*_with` functions return these alongside their
+/// result rather than logging them, so callers decide how to surface them.
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct Warning {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Warning {
+    fn new(message: impl Into<String>) -> Self {
+        Warning {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        Warning {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Parses the groups of the file at `path`. A key repeated within a group keeps its last
+/// value, as it always has here; use [`parse_desktop_entry_raw_with`] to reject such files.
 pub fn parse_desktop_entry_raw<P: AsRef<Path>>(path: P) -> Result<RawDesktopEntry> {
+    let content = std::fs::read_to_string(path)?;
+    parse_desktop_entry_raw_str(&content)
+}
+
+/// Parses non-UTF-8 content, such as legacy Latin-1 files, replacing invalid sequences with
+/// `U+FFFD` and recording a warning when that happens. Prefer the strict variants for files
+/// that are expected to follow the spec.
+pub fn parse_desktop_entry_bytes_lossy(bytes: &[u8]) -> Result<(RawDesktopEntry, Vec<Warning>)> {
+    let content = String::from_utf8_lossy(bytes);
+    let mut warnings = Vec::new();
+    if let Cow::Owned(_) = content {
+        warnings.push(Warning::new(
+            "Content is not valid UTF-8, invalid sequences were replaced",
+        ));
+    }
+    parse_desktop_entry_raw_str(&content).map(|raw| (raw, warnings))
+}
+
+/// Parses content that is already in memory. This and the other `*_str` functions are the core
+/// the file-reading functions wrap, and never touch the filesystem themselves. Like
+/// [`parse_desktop_entry_raw`], a repeated key keeps its last value.
+pub fn parse_desktop_entry_raw_str(content: &str) -> Result<RawDesktopEntry> {
+    parse_raw(content, &ParseOptions::keeping_last_value()).map(|parsed| parsed.groups)
+}
+
+pub fn parse_desktop_entry_raw_with<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<(RawDesktopEntry, Vec<Warning>)> {
+    let content = std::fs::read_to_string(path)?;
+    parse_desktop_entry_raw_str_with(&content, options)
+}
+
+pub fn parse_desktop_entry_raw_str_with(
+    content: &str,
+    options: &ParseOptions,
+) -> Result<(RawDesktopEntry, Vec<Warning>)> {
+    parse_raw(content, options).map(|parsed| (parsed.groups, parsed.warnings))
+}
+
+/// Like [`parse_desktop_entry_raw`], additionally returning the group names in the order they
+/// first appear in the file.
+pub fn parse_desktop_entry_raw_with_order<P: AsRef<Path>>(
+    path: P,
+) -> Result<(RawDesktopEntry, Vec<String>)> {
+    let content = std::fs::read_to_string(path)?;
+    parse_raw(&content, &ParseOptions::keeping_last_value())
+        .map(|parsed| (parsed.groups, parsed.order))
+}
+
+struct RawParse {
+    groups: RawDesktopEntry,
+    order: Vec<String>,
+    duplicate_groups: Vec<String>,
+    /// Every value of the keys repeated under [`DuplicateKeyPolicy::CollectAll`], by group and key.
+    repeated_values: HashMap<(String, String), Vec<String>>,
+    warnings: Vec<Warning>,
+}
+
+fn parse_raw(content: &str, options: &ParseOptions) -> Result<RawParse> {
     let mut groups: RawDesktopEntry = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+    let mut duplicate_groups: Vec<String> = Vec::new();
+    let mut repeated_values: HashMap<(String, String), Vec<String>> = HashMap::new();
+    let mut warnings: Vec<Warning> = Vec::new();
     let mut current_group: String = String::new();
 
-    let content = std::fs::read_to_string(path).map_err(|e| Error::IoError(e))?;
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
 
-    for line in content.lines() {
         if line.is_empty() || line.starts_with('#') {
             continue;
         };
 
-        if line.starts_with('[') && line.ends_with(']') {
-            current_group = line[1..line.len() - 1].to_string();
+        let header = line.trim();
+        let is_padded_header =
+            options.lenient && header.len() != line.len() && is_group_header(header);
+        if is_padded_header {
+            warnings.push(Warning::at(
+                line_number,
+                "Whitespace around group header ignored",
+            ));
+        }
+
+        if is_group_header(line) || is_padded_header {
+            current_group = header[1..header.len() - 1].to_string();
+            if !groups.contains_key(&current_group) {
+                groups.insert(current_group.clone(), HashMap::new());
+                order.push(current_group.clone());
+            } else if !duplicate_groups.contains(&current_group) {
+                duplicate_groups.push(current_group.clone());
+            }
             continue;
         }
 
         if current_group.is_empty() {
-            return Err(Error::FormatError(
-                "Entry found outside of group".to_string(),
+            if !options.assume_desktop_entry_group {
+                return Err(Error::FormatError(format!(
+                    "Entry found outside of group on line {line_number}"
+                )));
+            }
+
+            warnings.push(Warning::at(
+                line_number,
+                "Entry found outside of group, assuming [Desktop Entry]",
             ));
+            current_group = "Desktop Entry".to_string();
+            groups.insert(current_group.clone(), HashMap::new());
+            order.push(current_group.clone());
         }
 
         let entry: Vec<&str> = line.splitn(2, '=').collect();
 
         if entry.len() != 2 {
-            return Err(Error::FormatError("Entry not key/value".to_string()));
+            return Err(Error::FormatError(format!(
+                "Entry not key/value on line {line_number}"
+            )));
         }
 
-        groups
-            .entry(current_group.clone())
-            .or_insert_with(|| HashMap::new())
-            .insert(entry[0].trim().to_string(), entry[1].trim().to_string());
+        let value = if options.trim_values {
+            entry[1].trim()
+        } else {
+            entry[1]
+        };
+
+        let key = entry[0].trim();
+        let group = groups.entry(current_group.clone()).or_default();
+        let Some(existing) = group.get_mut(key) else {
+            group.insert(key.to_string(), value.to_string());
+            continue;
+        };
+
+        match options.duplicate_key_policy {
+            DuplicateKeyPolicy::Error => {
+                return Err(Error::FormatError(format!(
+                    "Duplicate key '{key}' in group [{current_group}] on line {line_number}"
+                )));
+            }
+            DuplicateKeyPolicy::First => warnings.push(Warning::at(
+                line_number,
+                format!(
+                    "Duplicate key '{key}' in group [{current_group}], keeping the first value"
+                ),
+            )),
+            DuplicateKeyPolicy::Last => {
+                warnings.push(Warning::at(
+                    line_number,
+                    format!(
+                        "Duplicate key '{key}' in group [{current_group}], keeping the last value"
+                    ),
+                ));
+                *existing = value.to_string();
+            }
+            DuplicateKeyPolicy::CollectAll => {
+                repeated_values
+                    .entry((current_group.clone(), key.to_string()))
+                    .or_insert_with(|| vec![existing.clone()])
+                    .push(value.to_string());
+                *existing = value.to_string();
+            }
+        }
     }
 
-    Ok(groups)
+    Ok(RawParse {
+        groups,
+        order,
+        duplicate_groups,
+        repeated_values,
+        warnings,
+    })
+}
+
+fn is_group_header(line: &str) -> bool {
+    line.starts_with('[') && line.ends_with(']')
 }
 
+/// Parses the file at `path` into a typed entry, reading it like [`parse_desktop_entry_raw`].
 pub fn parse_desktop_entry<P: AsRef<Path>>(path: P) -> Result<DesktopEntryType> {
     match parse_desktop_entry_raw(path) {
         Ok(raw_entry) => raw_entry.try_into(),
@@ -123,31 +487,421 @@ pub fn parse_desktop_entry<P: AsRef<Path>>(path: P) -> Result<DesktopEntryType>
     }
 }
 
-impl TryFrom<RawDesktopEntry> for DesktopEntryType {
-    type Error = Error;
+pub fn parse_desktop_entry_with<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<(DesktopEntryType, Vec<Warning>)> {
+    let content = std::fs::read_to_string(path)?;
+    parse_desktop_entry_str_with(&content, options)
+}
 
-    fn try_from(value: RawDesktopEntry) -> result::Result<Self, Self::Error> {
-        let group = value.get("Desktop Entry").ok_or(Error::FormatError(
-            "Desktop entry group missing!".to_string(),
-        ))?;
-        match group
+/// [`parse_desktop_entry_with`] for content that is already in memory.
+pub fn parse_desktop_entry_str_with(
+    content: &str,
+    options: &ParseOptions,
+) -> Result<(DesktopEntryType, Vec<Warning>)> {
+    let (raw_entry, mut warnings) = parse_desktop_entry_raw_str_with(content, options)?;
+    let (entry, entry_warnings) = DesktopEntryType::from_raw_with(&raw_entry, options)?;
+    warnings.extend(entry_warnings);
+    Ok((entry, warnings))
+}
+
+/// Reads just enough of the file at `path` to find the `Type` of its `[Desktop Entry]` group,
+/// without parsing or validating the rest. Returns `None` when the group has no `Type`.
+pub fn peek_entry_type<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut in_main_group = false;
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with('[') && line.ends_with(']') {
+            if in_main_group {
+                break;
+            }
+            in_main_group = line == "[Desktop Entry]";
+        } else if in_main_group
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "Type"
+        {
+            return Ok(Some(value.trim().to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// [`parse_desktop_entry`] for quick tools: on failure, a diagnostic naming the file is
+/// written to `out` and `None` is returned.
+pub fn parse_or_report<P: AsRef<Path>>(path: P, mut out: impl Write) -> Option<DesktopEntryType> {
+    match parse_desktop_entry(&path) {
+        Ok(entry) => Some(entry),
+        Err(error) => {
+            // There's nowhere left to report a failure to write the report to.
+            let _ = writeln!(out, "{}: {error}", path.as_ref().display());
+            None
+        }
+    }
+}
+
+/// A parsed entry together with the file it was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(unused)]
+pub struct ParsedDesktopFile {
+    path: PathBuf,
+    entry: DesktopEntryType,
+    modified: Option<SystemTime>,
+}
+
+impl ParsedDesktopFile {
+    /// The path of the file the entry was parsed from, as it was passed to
+    /// [`parse_desktop_file`].
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entry(&self) -> &DesktopEntryType {
+        &self.entry
+    }
+
+    pub fn into_entry(self) -> DesktopEntryType {
+        self.entry
+    }
+
+    /// The modification time of the file when it was parsed, if the platform reports one.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// Whether the file changed since it was parsed, judging by its modification time. A file
+    /// that is gone, or whose modification time isn't known, counts as stale.
+    pub fn is_stale(&self) -> bool {
+        self.modified.is_none() || modified_time(&self.path) != self.modified
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// [`parse_desktop_entry`], keeping the path the entry came from and its modification time.
+pub fn parse_desktop_file<P: AsRef<Path>>(path: P) -> Result<ParsedDesktopFile> {
+    // Taken before reading, so that a change made while the file is read makes it stale.
+    let modified = modified_time(path.as_ref());
+    Ok(ParsedDesktopFile {
+        entry: parse_desktop_entry(&path)?,
+        path: path.as_ref().to_path_buf(),
+        modified,
+    })
+}
+
+pub fn parse_application_file<P: AsRef<Path>>(path: P) -> Result<ApplicationDesktopEntry> {
+    into_application(parse_desktop_entry(path)?)
+}
+
+pub fn parse_link_file<P: AsRef<Path>>(path: P) -> Result<LinkDesktopEntry> {
+    into_link(parse_desktop_entry(path)?)
+}
+
+/// Parses a `.directory` file, as referenced by `.menu` files.
+pub fn parse_directory_file<P: AsRef<Path>>(path: P) -> Result<DirectoryDesktopEntry> {
+    into_directory(parse_desktop_entry(path)?)
+}
+
+fn into_application(entry: DesktopEntryType) -> Result<ApplicationDesktopEntry> {
+    match entry {
+        DesktopEntryType::Application(entry) => Ok(entry),
+        _ => Err(Error::FormatError(
+            "Entry is not of type Application".to_string(),
+        )),
+    }
+}
+
+fn into_link(entry: DesktopEntryType) -> Result<LinkDesktopEntry> {
+    match entry {
+        DesktopEntryType::Link(entry) => Ok(entry),
+        _ => Err(Error::FormatError("Entry is not of type Link".to_string())),
+    }
+}
+
+fn into_directory(entry: DesktopEntryType) -> Result<DirectoryDesktopEntry> {
+    match entry {
+        DesktopEntryType::Directory(entry) => Ok(entry),
+        _ => Err(Error::FormatError(
+            "Entry is not of type Directory".to_string(),
+        )),
+    }
+}
+
+/// Applies `f(group, key, value)` to every value of a raw entry, replacing each value with the
+/// result. Useful for sanitizing values between the raw parse and the typed conversion.
+pub fn transform_raw(
+    raw: RawDesktopEntry,
+    f: impl Fn(&str, &str, &str) -> String,
+) -> RawDesktopEntry {
+    raw.into_iter()
+        .map(|(group, entries)| {
+            let entries = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = f(&group, &key, &value);
+                    (key, value)
+                })
+                .collect();
+            (group, entries)
+        })
+        .collect()
+}
+
+impl DesktopEntryType {
+    /// The canonical `Type` value of the entry.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DesktopEntryType::Application(_) => "Application",
+            DesktopEntryType::Link(_) => "Link",
+            DesktopEntryType::Directory(_) => "Directory",
+        }
+    }
+
+    /// A one-line description for logs and listings, like `Application "Firefox" (exec:
+    /// firefox %u, categories: Network;WebBrowser)`.
+    pub fn summary(&self) -> String {
+        let (name, details) = match self {
+            DesktopEntryType::Application(entry) => {
+                let mut details = Vec::new();
+                if let Some(exec) = &entry.exec {
+                    details.push(format!("exec: {exec}"));
+                }
+                if let Some(categories) = &entry.categories {
+                    details.push(format!("categories: {}", categories.join(";")));
+                }
+                (&entry.name, details)
+            }
+            DesktopEntryType::Link(entry) => (
+                &entry.name,
+                entry.url.iter().map(|url| format!("url: {url}")).collect(),
+            ),
+            DesktopEntryType::Directory(entry) => (&entry.name, Vec::new()),
+        };
+
+        let mut summary = format!("{} \"{name}\"", self.type_name());
+        if !details.is_empty() {
+            summary.push_str(&format!(" ({})", details.join(", ")));
+        }
+        summary
+    }
+
+    pub fn from_raw_with(
+        raw: &RawDesktopEntry,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<Warning>)> {
+        let mut warnings = Vec::new();
+        let mut group = Cow::Borrowed(main_group(raw, options, &mut warnings)?);
+        if options.accept_numeric_bools {
+            group = Cow::Owned(numeric_bools_to_literals(&group, &mut warnings));
+        }
+        if options.lenient
+            && !group.contains_key("Name")
+            && let Some((locale, name)) = localized_values(&group, "Name").into_iter().next()
+        {
+            warnings.push(Warning::new(format!(
+                "Missing required key 'Name', using Name[{locale}] instead"
+            )));
+            group.to_mut().insert("Name".to_string(), name);
+        }
+        // Older KDE versions wrote `URL[$e]`, marking a value whose environment variables are
+        // to be expanded.
+        if options.lenient
+            && !group.contains_key("URL")
+            && let Some(url) = group.get("URL[$e]").cloned()
+        {
+            warnings.push(Warning::new(
+                "Legacy key 'URL[$e]' read as 'URL', with environment variables expanded",
+            ));
+            let url = exec::expand_env_vars(&url, &mut warnings);
+            let group = group.to_mut();
+            group.remove("URL[$e]");
+            group.insert("URL".to_string(), url);
+        }
+        let group = group.as_ref();
+        let entry = match group
             .get("Type")
             .ok_or(Error::FormatError("Entry type missing!".to_string()))?
-            .as_str()
+            .trim()
         {
-            "Application" => {
-                return ApplicationDesktopEntry::try_from(group)
-                    .map(|e| DesktopEntryType::Application(e));
-            }
-            "Link" => {
-                return LinkDesktopEntry::try_from(group).map(|e| DesktopEntryType::Link(e));
-            }
-            "Directory" => {
-                return DirectoryDesktopEntry::try_from(group)
-                    .map(|e| DesktopEntryType::Directory(e));
+            "Application" => ApplicationDesktopEntry::try_from(group).and_then(|mut entry| {
+                entry.desktop_actions = action::parse_actions(raw, &entry)?;
+                Ok(DesktopEntryType::Application(entry))
+            }),
+            "Link" if options.lenient && !group.contains_key("URL") => {
+                warnings.push(Warning::new("Missing required key 'URL'"));
+                LinkDesktopEntry::from_group(group).map(DesktopEntryType::Link)
             }
-            unknown => return Err(Error::FormatError(format!("Unknown entry type {unknown}"))),
-        }
+            "Link" => LinkDesktopEntry::try_from(group).map(DesktopEntryType::Link),
+            "Directory" => DirectoryDesktopEntry::try_from(group).map(DesktopEntryType::Directory),
+            unknown => Err(Error::FormatError(format!("Unknown entry type {unknown}"))),
+        }?;
+        Ok((entry, warnings))
+    }
+}
+
+fn main_group<'a>(
+    raw: &'a RawDesktopEntry,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<&'a HashMap<String, String>> {
+    if let Some(group) = raw.get("Desktop Entry") {
+        return Ok(group);
+    }
+
+    if options.lenient
+        && let Some((name, group)) = raw
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Desktop Entry"))
+    {
+        warnings.push(Warning::new(format!(
+            "Group [{name}] should be spelled [Desktop Entry]"
+        )));
+        return Ok(group);
+    }
+
+    Err(Error::FormatError(
+        "Desktop entry group missing!".to_string(),
+    ))
+}
+
+/// The keys of the main group that hold booleans.
+const BOOLEAN_KEYS: &[&str] = &[
+    "NoDisplay",
+    "Hidden",
+    "DBusActivatable",
+    "Terminal",
+    "StartupNotify",
+    "PrefersNonDefaultGPU",
+    "SingleMainWindow",
+];
+
+fn numeric_bools_to_literals(
+    group: &HashMap<String, String>,
+    warnings: &mut Vec<Warning>,
+) -> HashMap<String, String> {
+    let mut group = group.clone();
+    for key in BOOLEAN_KEYS {
+        let Some(value) = group.get_mut(*key) else {
+            continue;
+        };
+        let literal = match value.trim() {
+            "0" => "false",
+            "1" => "true",
+            _ => continue,
+        };
+        warnings.push(Warning::new(format!(
+            "Boolean key '{key}' is '{value}' instead of '{literal}'"
+        )));
+        *value = literal.to_string();
+    }
+    group
+}
+
+impl TryFrom<RawDesktopEntry> for DesktopEntryType {
+    type Error = Error;
+
+    fn try_from(value: RawDesktopEntry) -> result::Result<Self, Self::Error> {
+        DesktopEntryType::try_from(&value)
+    }
+}
+
+impl TryFrom<&RawDesktopEntry> for DesktopEntryType {
+    type Error = Error;
+
+    fn try_from(value: &RawDesktopEntry) -> result::Result<Self, Self::Error> {
+        DesktopEntryType::from_raw_with(value, &ParseOptions::default()).map(|(entry, _)| entry)
+    }
+}
+
+/// Keys whose values are kept when they consist only of whitespace. For other string keys, such
+/// as `Icon` or `Path`, a whitespace-only value is meaningless and treated as absent.
+pub const SIGNIFICANT_WHITESPACE_KEYS: &[&str] = &["Name", "GenericName", "Comment", "Exec"];
+
+pub(crate) fn string_value(entry: &HashMap<String, String>, key: &str) -> Option<String> {
+    let value = entry.get(key)?;
+    if value.trim().is_empty() && !SIGNIFICANT_WHITESPACE_KEYS.contains(&key) {
+        return None;
+    }
+    Some(value.clone())
+}
+
+/// A boolean key, `false` unless the value is `true`. Surrounding whitespace is ignored even when
+/// [`ParseOptions::trim_values`] is unset.
+pub(crate) fn bool_value(entry: &HashMap<String, String>, key: &str) -> Option<bool> {
+    entry
+        .get(key)
+        .map(|value| value.trim().parse().is_ok_and(|value| value))
+}
+
+/// A list key split into its items, ignoring whitespace around the whole value like
+/// [`bool_value`].
+pub(crate) fn list_value(entry: &HashMap<String, String>, key: &str) -> Option<Vec<String>> {
+    entry.get(key).map(|value| split_list(value.trim()))
+}
+
+pub(crate) fn localized_values(
+    entry: &HashMap<String, String>,
+    key: &str,
+) -> BTreeMap<String, String> {
+    entry
+        .iter()
+        .filter_map(|(entry_key, value)| {
+            let locale = entry_key
+                .strip_prefix(key)?
+                .strip_prefix('[')?
+                .strip_suffix(']')?;
+            Some((locale.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+pub(crate) fn extension_values(entry: &HashMap<String, String>) -> BTreeMap<String, String> {
+    entry
+        .iter()
+        .filter(|(key, _)| key.starts_with("X-"))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+impl FromStr for DesktopEntryType {
+    type Err = Error;
+
+    fn from_str(content: &str) -> result::Result<Self, Self::Err> {
+        parse_desktop_entry_raw_str(content)?.try_into()
+    }
+}
+
+/// Parses the contents of a whole file, failing if it isn't an Application entry.
+impl TryFrom<&str> for ApplicationDesktopEntry {
+    type Error = Error;
+
+    fn try_from(content: &str) -> result::Result<Self, Self::Error> {
+        into_application(content.parse()?)
+    }
+}
+
+/// Parses the contents of a whole file, failing if it isn't a Link entry.
+impl TryFrom<&str> for LinkDesktopEntry {
+    type Error = Error;
+
+    fn try_from(content: &str) -> result::Result<Self, Self::Error> {
+        into_link(content.parse()?)
+    }
+}
+
+/// Parses the contents of a whole file, failing if it isn't a Directory entry.
+impl TryFrom<&str> for DirectoryDesktopEntry {
+    type Error = Error;
+
+    fn try_from(content: &str) -> result::Result<Self, Self::Error> {
+        into_directory(content.parse()?)
     }
 }
 
@@ -156,44 +910,41 @@ impl TryFrom<&HashMap<String, String>> for ApplicationDesktopEntry {
 
     fn try_from(entry: &HashMap<String, String>) -> result::Result<Self, Self::Error> {
         Ok(ApplicationDesktopEntry {
-            version: entry.get("Version").cloned(),
+            version: string_value(entry, "Version"),
             name: entry
                 .get("Name")
                 .ok_or(Error::FormatError(
                     "Missing required key 'Name'".to_string(),
                 ))?
                 .to_string(),
-            generic_name: entry.get("GenericName").cloned(),
-            no_display: entry
-                .get("NoDisplay")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            comment: entry.get("Comment").cloned(),
-            icon: entry.get("Icon").cloned(),
-            hidden: entry
-                .get("Hidden")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            only_show_in: entry.get("OnlyShowIn").cloned(),
-            not_show_in: entry.get("NotShowIn").cloned(),
-            try_exec: entry.get("TryExec").cloned(),
-            exec: entry.get("Exec").cloned(),
-            path: entry.get("Path").cloned(),
-            terminal: entry
-                .get("Terminal")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            actions: entry.get("Actions").cloned(),
-            mime_type: entry.get("MimeType").cloned(),
-            categories: entry.get("Categories").cloned(),
-            keywords: entry.get("Keywords").cloned(),
-            startup_notify: entry
-                .get("StartupNotify")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            startup_wm_class: entry.get("StartupWMClass").cloned(),
-            prefers_non_default_gpu: entry
-                .get("PrefersNonDefaultGPU")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            single_main_window: entry
-                .get("SingleMainWindow")
-                .map(|value| value.parse().is_ok_and(|e| e)),
+            name_localized: localized_values(entry, "Name"),
+            generic_name: string_value(entry, "GenericName"),
+            generic_name_localized: localized_values(entry, "GenericName"),
+            no_display: bool_value(entry, "NoDisplay"),
+            comment: string_value(entry, "Comment"),
+            comment_localized: localized_values(entry, "Comment"),
+            icon: string_value(entry, "Icon"),
+            hidden: bool_value(entry, "Hidden"),
+            only_show_in: list_value(entry, "OnlyShowIn"),
+            not_show_in: list_value(entry, "NotShowIn"),
+            try_exec: string_value(entry, "TryExec"),
+            exec: string_value(entry, "Exec"),
+            path: string_value(entry, "Path"),
+            terminal: bool_value(entry, "Terminal"),
+            actions: list_value(entry, "Actions"),
+            mime_type: list_value(entry, "MimeType"),
+            categories: list_value(entry, "Categories"),
+            keywords: list_value(entry, "Keywords"),
+            keywords_localized: localized_values(entry, "Keywords")
+                .into_iter()
+                .map(|(locale, value)| (locale, split_list(value.trim())))
+                .collect(),
+            startup_notify: bool_value(entry, "StartupNotify"),
+            startup_wm_class: string_value(entry, "StartupWMClass"),
+            prefers_non_default_gpu: bool_value(entry, "PrefersNonDefaultGPU"),
+            single_main_window: bool_value(entry, "SingleMainWindow"),
+            desktop_actions: Vec::new(),
+            extensions: extension_values(entry),
         })
     }
 }
@@ -202,29 +953,37 @@ impl TryFrom<&HashMap<String, String>> for LinkDesktopEntry {
     type Error = Error;
 
     fn try_from(entry: &HashMap<String, String>) -> result::Result<Self, Self::Error> {
+        let link = LinkDesktopEntry::from_group(entry)?;
+        if link.url.is_none() {
+            return Err(Error::FormatError("Missing required key 'URL'".to_string()));
+        }
+        Ok(link)
+    }
+}
+
+impl LinkDesktopEntry {
+    // Builds the entry without requiring `URL`, which strict parsing checks separately.
+    fn from_group(entry: &HashMap<String, String>) -> Result<Self> {
         Ok(LinkDesktopEntry {
-            version: entry.get("Version").cloned(),
+            version: string_value(entry, "Version"),
             name: entry
                 .get("Name")
                 .ok_or(Error::FormatError(
                     "Missing required key 'Name'".to_string(),
                 ))?
                 .to_string(),
-            generic_name: entry.get("GenericName").cloned(),
-            no_display: entry
-                .get("NoDisplay")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            comment: entry.get("Comment").cloned(),
-            icon: entry.get("Icon").cloned(),
-            hidden: entry
-                .get("Hidden")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            only_show_in: entry.get("OnlyShowIn").cloned(),
-            not_show_in: entry.get("NotShowIn").cloned(),
-            url: entry
-                .get("URL")
-                .ok_or(Error::FormatError("Missing required key 'URL'".to_string()))?
-                .to_string(),
+            name_localized: localized_values(entry, "Name"),
+            generic_name: string_value(entry, "GenericName"),
+            generic_name_localized: localized_values(entry, "GenericName"),
+            no_display: bool_value(entry, "NoDisplay"),
+            comment: string_value(entry, "Comment"),
+            comment_localized: localized_values(entry, "Comment"),
+            icon: string_value(entry, "Icon"),
+            hidden: bool_value(entry, "Hidden"),
+            only_show_in: list_value(entry, "OnlyShowIn"),
+            not_show_in: list_value(entry, "NotShowIn"),
+            url: string_value(entry, "URL"),
+            extensions: extension_values(entry),
         })
     }
 }
@@ -234,24 +993,206 @@ impl TryFrom<&HashMap<String, String>> for DirectoryDesktopEntry {
 
     fn try_from(entry: &HashMap<String, String>) -> result::Result<Self, Self::Error> {
         Ok(DirectoryDesktopEntry {
-            version: entry.get("Version").cloned(),
+            version: string_value(entry, "Version"),
             name: entry
                 .get("Name")
                 .ok_or(Error::FormatError(
                     "Missing required key 'Name'".to_string(),
                 ))?
                 .to_string(),
-            generic_name: entry.get("GenericName").cloned(),
-            no_display: entry
-                .get("NoDisplay")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            comment: entry.get("Comment").cloned(),
-            icon: entry.get("Icon").cloned(),
-            hidden: entry
-                .get("Hidden")
-                .map(|value| value.parse().is_ok_and(|e| e)),
-            only_show_in: entry.get("OnlyShowIn").cloned(),
-            not_show_in: entry.get("NotShowIn").cloned(),
+            name_localized: localized_values(entry, "Name"),
+            generic_name: string_value(entry, "GenericName"),
+            generic_name_localized: localized_values(entry, "GenericName"),
+            no_display: bool_value(entry, "NoDisplay"),
+            comment: string_value(entry, "Comment"),
+            comment_localized: localized_values(entry, "Comment"),
+            icon: string_value(entry, "Icon"),
+            hidden: bool_value(entry, "Hidden"),
+            only_show_in: list_value(entry, "OnlyShowIn"),
+            not_show_in: list_value(entry, "NotShowIn"),
+            extensions: extension_values(entry),
         })
     }
 }
+
+impl ApplicationDesktopEntry {
+    /// Overrides fields of `self` with those set in `other`, leaving fields `other` doesn't
+    /// set untouched. `name` is always taken from `other`, translations are merged per
+    /// locale, and list fields like `Categories` are replaced as a whole rather than
+    /// concatenated.
+    pub fn merge(&mut self, other: &ApplicationDesktopEntry) {
+        self.name = other.name.clone();
+        self.name_localized.extend(other.name_localized.clone());
+        self.generic_name_localized
+            .extend(other.generic_name_localized.clone());
+        self.comment_localized
+            .extend(other.comment_localized.clone());
+        self.keywords_localized
+            .extend(other.keywords_localized.clone());
+        self.extensions.extend(other.extensions.clone());
+        merge_field(&mut self.version, &other.version);
+        merge_field(&mut self.generic_name, &other.generic_name);
+        merge_field(&mut self.no_display, &other.no_display);
+        merge_field(&mut self.comment, &other.comment);
+        merge_field(&mut self.icon, &other.icon);
+        merge_field(&mut self.hidden, &other.hidden);
+        merge_field(&mut self.only_show_in, &other.only_show_in);
+        merge_field(&mut self.not_show_in, &other.not_show_in);
+        merge_field(&mut self.try_exec, &other.try_exec);
+        merge_field(&mut self.exec, &other.exec);
+        merge_field(&mut self.path, &other.path);
+        merge_field(&mut self.terminal, &other.terminal);
+        merge_field(&mut self.actions, &other.actions);
+        merge_field(&mut self.mime_type, &other.mime_type);
+        merge_field(&mut self.categories, &other.categories);
+        merge_field(&mut self.keywords, &other.keywords);
+        merge_field(&mut self.startup_notify, &other.startup_notify);
+        merge_field(&mut self.startup_wm_class, &other.startup_wm_class);
+        merge_field(
+            &mut self.prefers_non_default_gpu,
+            &other.prefers_non_default_gpu,
+        );
+        merge_field(&mut self.single_main_window, &other.single_main_window);
+        if other.actions.is_some() {
+            self.desktop_actions.clone_from(&other.desktop_actions);
+        }
+    }
+}
+
+fn merge_field<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+    if other.is_some() {
+        field.clone_from(other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    const REPEATED_NAME: &str = "[Desktop Entry]\nType=Application\nName=First\nName=Last\n";
+
+    #[test]
+    fn plain_parse_keeps_the_last_repeated_value() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", REPEATED_NAME);
+
+        let raw = parse_desktop_entry_raw(&path).unwrap();
+        assert_eq!(raw["Desktop Entry"]["Name"], "Last");
+        let DesktopEntryType::Application(entry) = parse_desktop_entry(&path).unwrap() else {
+            panic!("not an application");
+        };
+        assert_eq!(entry.name, "Last");
+    }
+
+    #[test]
+    fn every_entry_point_without_options_keeps_the_last_repeated_value() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", REPEATED_NAME);
+        let name = |entry: DesktopEntryType| match entry {
+            DesktopEntryType::Application(entry) => entry.name,
+            _ => panic!("not an application"),
+        };
+
+        assert_eq!(name(REPEATED_NAME.parse().unwrap()), "Last");
+        assert_eq!(
+            ApplicationDesktopEntry::try_from(REPEATED_NAME)
+                .unwrap()
+                .name,
+            "Last"
+        );
+        assert_eq!(
+            parse_desktop_entry_raw_str(REPEATED_NAME).unwrap()["Desktop Entry"]["Name"],
+            "Last"
+        );
+        let (raw, order) = parse_desktop_entry_raw_with_order(&path).unwrap();
+        assert_eq!(raw["Desktop Entry"]["Name"], "Last");
+        assert_eq!(order, ["Desktop Entry"]);
+        let file = parse_desktop_file_raw(&path).unwrap();
+        assert_eq!(file.values("Desktop Entry", "Name"), ["Last"]);
+        let (raw, _) = parse_desktop_entry_bytes_lossy(REPEATED_NAME.as_bytes()).unwrap();
+        assert_eq!(raw["Desktop Entry"]["Name"], "Last");
+
+        let mut names = Vec::new();
+        parse_desktop_entries_stream(REPEATED_NAME.as_bytes(), |entry| {
+            names.push(name(entry.unwrap()));
+        });
+        assert_eq!(names, ["Last"]);
+    }
+
+    #[test]
+    fn default_options_reject_repeated_keys() {
+        let error =
+            parse_desktop_entry_raw_str_with(REPEATED_NAME, &ParseOptions::default()).unwrap_err();
+        assert!(error.to_string().contains("Duplicate key 'Name'"));
+    }
+
+    const GERMAN_NAME_ONLY: &str = "[Desktop Entry]\nType=Application\nName[de]=Rechner\n";
+
+    #[test]
+    fn strict_parse_requires_name() {
+        let error =
+            parse_desktop_entry_str_with(GERMAN_NAME_ONLY, &ParseOptions::default()).unwrap_err();
+        assert!(error.to_string().contains("Name"));
+    }
+
+    #[test]
+    fn lenient_parse_falls_back_to_a_translated_name() {
+        let options = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        let (entry, warnings) = parse_desktop_entry_str_with(GERMAN_NAME_ONLY, &options).unwrap();
+        let DesktopEntryType::Application(entry) = entry else {
+            panic!("not an application");
+        };
+        assert_eq!(entry.name, "Rechner");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "Missing required key 'Name', using Name[de] instead"
+        );
+    }
+
+    #[test]
+    fn whitespace_only_values_are_absent_unless_significant() {
+        let options = ParseOptions {
+            trim_values: false,
+            ..ParseOptions::default()
+        };
+        let (entry, _) = parse_desktop_entry_str_with(
+            "[Desktop Entry]\nType=Application\nName=  \nComment= \nExec=   \nIcon=   \nPath= \n",
+            &options,
+        )
+        .unwrap();
+        let DesktopEntryType::Application(entry) = entry else {
+            panic!("not an application");
+        };
+        assert_eq!(entry.icon, None);
+        assert_eq!(entry.path, None);
+        assert_eq!(entry.name, "  ");
+        assert_eq!(entry.comment.as_deref(), Some(" "));
+        assert_eq!(entry.exec.as_deref(), Some("   "));
+    }
+
+    #[test]
+    fn untrimmed_values_still_type_correctly() {
+        let options = ParseOptions {
+            trim_values: false,
+            ..ParseOptions::default()
+        };
+        let content = "[Desktop Entry]\nType = Application \nName= App \nExec=app\n\
+            Terminal= true\nCategories= Utility;Development; \n";
+        let (raw, _) = parse_desktop_entry_raw_str_with(content, &options).unwrap();
+        assert_eq!(raw["Desktop Entry"]["Type"], " Application ");
+
+        let (entry, _) = parse_desktop_entry_str_with(content, &options).unwrap();
+        let DesktopEntryType::Application(entry) = entry else {
+            panic!("not an application");
+        };
+        assert_eq!(entry.name, " App ");
+        assert_eq!(entry.terminal, Some(true));
+        let categories = entry.categories.unwrap();
+        assert_eq!(categories, ["Utility", "Development"]);
+    }
+}
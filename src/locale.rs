@@ -0,0 +1,244 @@
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+
+use crate::text::{strip_markup, truncate_graphemes};
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry, LinkDesktopEntry};
+
+/// The locale suffixes to try for `locale`, most specific first, per the spec's matching rules:
+/// `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER`, then `lang`. Any `.ENCODING` part
+/// is ignored.
+pub(crate) fn locale_candidates(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    let rest = rest.split_once('.').map_or(rest, |(rest, _)| rest);
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+
+    if lang.is_empty() || lang == "C" || lang == "POSIX" {
+        return Vec::new();
+    }
+
+    let mut candidates = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        candidates.push(format!("{lang}_{country}@{modifier}"));
+    }
+    if let Some(country) = country {
+        candidates.push(format!("{lang}_{country}"));
+    }
+    if let Some(modifier) = modifier {
+        candidates.push(format!("{lang}@{modifier}"));
+    }
+    candidates.push(lang.to_string());
+    candidates
+}
+
+/// The messages locale from the environment: the first non-empty of `$LC_ALL`,
+/// `$LC_MESSAGES` and `$LANG`.
+fn env_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// The locales translations should be looked up in, most preferred first, following gettext:
+/// the colon-separated `$LANGUAGE` list, then the messages locale from `$LC_ALL`,
+/// `$LC_MESSAGES` or `$LANG`. Like gettext, `$LANGUAGE` is ignored when the messages locale is
+/// unset or `C`, and the list is empty then.
+pub fn preferred_locales() -> Vec<String> {
+    let Some(locale) = env_locale().filter(|locale| !locale_candidates(locale).is_empty()) else {
+        return Vec::new();
+    };
+
+    let mut locales: Vec<String> = env::var("LANGUAGE")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|language| !language.is_empty())
+        .map(str::to_string)
+        .collect();
+    locales.push(locale);
+    dedup_keeping_order(&mut locales);
+    locales
+}
+
+/// [`locale_candidates`] of each of the [`preferred_locales`], in order.
+fn env_locale_candidates() -> Vec<String> {
+    let mut candidates: Vec<String> = preferred_locales()
+        .iter()
+        .flat_map(|locale| locale_candidates(locale))
+        .collect();
+    dedup_keeping_order(&mut candidates);
+    candidates
+}
+
+fn dedup_keeping_order(values: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    values.retain(|value| seen.insert(value.clone()));
+}
+
+pub(crate) fn localized<'a>(
+    default: &'a str,
+    translations: &'a BTreeMap<String, String>,
+    locale: &str,
+) -> &'a str {
+    localized_optional(Some(default), translations, locale).unwrap_or(default)
+}
+
+/// [`localized`] for optional keys: a translation is used even when the untranslated value is
+/// missing.
+pub(crate) fn localized_optional<'a>(
+    default: Option<&'a str>,
+    translations: &'a BTreeMap<String, String>,
+    locale: &str,
+) -> Option<&'a str> {
+    translated(translations, &locale_candidates(locale))
+        .map(String::as_str)
+        .or(default)
+}
+
+/// The translation for the first of `candidates` that has one.
+fn translated<'a, T>(
+    translations: &'a BTreeMap<String, T>,
+    candidates: &[String],
+) -> Option<&'a T> {
+    candidates
+        .iter()
+        .find_map(|candidate| translations.get(candidate))
+}
+
+macro_rules! impl_localized_name {
+    ($($entry:ty),*) => {$(
+        impl $entry {
+            /// The `Name` translation best matching `locale` (e.g. `de_DE.UTF-8`), falling back
+            /// to the untranslated name.
+            pub fn localized_name(&self, locale: &str) -> &str {
+                localized(&self.name, &self.name_localized, locale)
+            }
+
+            /// [`Self::localized_name`] for the [`preferred_locales`], trying each in order.
+            pub fn localized_name_for_env(&self) -> &str {
+                translated(&self.name_localized, &env_locale_candidates()).unwrap_or(&self.name)
+            }
+
+            /// The `GenericName` translation best matching `locale`, with the same fallback as
+            /// [`Self::localized_name`].
+            pub fn localized_generic_name(&self, locale: &str) -> Option<&str> {
+                localized_optional(
+                    self.generic_name.as_deref(),
+                    &self.generic_name_localized,
+                    locale,
+                )
+            }
+
+            /// The `Comment` translation best matching `locale`, with the same fallback as
+            /// [`Self::localized_name`].
+            pub fn localized_comment(&self, locale: &str) -> Option<&str> {
+                localized_optional(self.comment.as_deref(), &self.comment_localized, locale)
+            }
+
+            /// [`Self::localized_comment`] for the [`preferred_locales`], trying each in order.
+            pub fn localized_comment_for_env(&self) -> Option<&str> {
+                translated(&self.comment_localized, &env_locale_candidates())
+                    .map(String::as_str)
+                    .or(self.comment.as_deref())
+            }
+
+            /// [`Self::localized_comment_for_env`], shortened at a grapheme boundary like
+            /// [`Self::display_name`], with the same approximation of graphemes.
+            pub fn comment_truncated(&self, max_len: usize) -> Option<String> {
+                self.localized_comment_for_env()
+                    .map(|comment| truncate_graphemes(comment, max_len))
+            }
+
+            /// [`Self::localized_name`] with simple markup such as `<b>` and `<i>` removed, for
+            /// contexts that don't render markup. This is a workaround for files that wrongly
+            /// use markup; the spec doesn't allow it.
+            pub fn plain_name(&self, locale: &str) -> String {
+                strip_markup(self.localized_name(locale))
+            }
+
+            /// [`Self::localized_comment`] with markup removed like [`Self::plain_name`] does.
+            pub fn plain_comment(&self, locale: &str) -> Option<String> {
+                self.localized_comment(locale).map(strip_markup)
+            }
+
+            /// The localized name, shortened at a grapheme boundary to at most `max_len`
+            /// graphemes including a trailing `…` when it doesn't fit.
+            ///
+            /// Graphemes are approximated without the full Unicode segmentation rules:
+            /// combining marks, the vowel signs of common Indic and Southeast Asian scripts,
+            /// Hangul jamo, emoji sequences and flags stay whole, but rarer scripts and
+            /// constructs such as Indic conjuncts may still be split.
+            pub fn display_name(&self, locale: &str, max_len: Option<usize>) -> String {
+                let name = self.localized_name(locale);
+                match max_len {
+                    Some(max_len) => truncate_graphemes(name, max_len),
+                    None => name.to_string(),
+                }
+            }
+        }
+    )*};
+}
+
+impl_localized_name!(
+    ApplicationDesktopEntry,
+    LinkDesktopEntry,
+    DirectoryDesktopEntry
+);
+
+impl ApplicationDesktopEntry {
+    /// The `Keywords` translation best matching `locale`, falling back to the untranslated
+    /// keywords.
+    pub fn localized_keywords(&self, locale: &str) -> Option<&[String]> {
+        translated(&self.keywords_localized, &locale_candidates(locale))
+            .or(self.keywords.as_ref())
+            .map(Vec::as_slice)
+    }
+
+    /// [`Self::localized_keywords`] for the [`preferred_locales`], trying each in order, with
+    /// surrounding whitespace trimmed from each keyword.
+    pub fn keywords_for_env(&self) -> Vec<String> {
+        translated(&self.keywords_localized, &env_locale_candidates())
+            .or(self.keywords.as_ref())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|keyword| keyword.trim().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+
+    use crate::ApplicationDesktopEntry;
+    use crate::testing::with_env;
+
+    #[test]
+    fn keywords_for_env_uses_the_locale_from_lang() {
+        let entry: ApplicationDesktopEntry = "[Desktop Entry]\nType=Application\nName=a\nExec=a\n\
+            Keywords=one;two;\nKeywords[fr]=un;deux;\n"
+            .try_into()
+            .unwrap();
+        let vars = |lang: &'static str| {
+            [
+                ("LANGUAGE", None),
+                ("LC_ALL", None),
+                ("LC_MESSAGES", None),
+                ("LANG", Some(OsStr::new(lang))),
+            ]
+        };
+
+        with_env(&vars("fr_FR.UTF-8"), || {
+            assert_eq!(entry.keywords_for_env(), ["un", "deux"]);
+        });
+        with_env(&vars("de_DE.UTF-8"), || {
+            assert_eq!(entry.keywords_for_env(), ["one", "two"]);
+        });
+    }
+}
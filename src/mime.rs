@@ -0,0 +1,93 @@
+use crate::ApplicationDesktopEntry;
+
+/// Whether `mime_type` has the `type/subtype` shape, without checking it against a registry.
+pub(crate) fn is_valid_mime_type(mime_type: &str) -> bool {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| !c.is_whitespace() && !c.is_control() && c != '/')
+    };
+    mime_type
+        .split_once('/')
+        .is_some_and(|(media_type, subtype)| valid_part(media_type) && valid_part(subtype))
+}
+
+/// Whether two MIME types match, where a `*` part on either side matches any value.
+fn mime_glob_matches(left: &str, right: &str) -> bool {
+    let part_matches =
+        |left: &str, right: &str| left == "*" || right == "*" || left.eq_ignore_ascii_case(right);
+    match (left.split_once('/'), right.split_once('/')) {
+        (Some((left_type, left_subtype)), Some((right_type, right_subtype))) => {
+            part_matches(left_type, right_type) && part_matches(left_subtype, right_subtype)
+        }
+        _ => left.eq_ignore_ascii_case(right),
+    }
+}
+
+impl ApplicationDesktopEntry {
+    /// The MIME types listed in `MimeType`.
+    pub fn mime_types(&self) -> &[String] {
+        self.mime_type.as_deref().unwrap_or_default()
+    }
+
+    /// Whether the entry declares `mime_type`. MIME types compare case-insensitively.
+    pub fn handles_mime_type(&self, mime_type: &str) -> bool {
+        self.mime_types()
+            .iter()
+            .any(|declared| declared.eq_ignore_ascii_case(mime_type))
+    }
+
+    /// Like [`Self::handles_mime_type`], but `*` matches any type or subtype, both in
+    /// `pattern` (`image/*` matches a declared `image/png`) and in the declared types (a
+    /// declared `image/*` matches `image/png`).
+    pub fn handles_mime_glob(&self, pattern: &str) -> bool {
+        self.mime_types()
+            .iter()
+            .any(|declared| mime_glob_matches(declared, pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ApplicationDesktopEntry;
+
+    fn entry(mime_type: &str) -> ApplicationDesktopEntry {
+        format!("[Desktop Entry]\nType=Application\nName=a\nExec=a\nMimeType={mime_type}\n")
+            .as_str()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn concrete_matches_concrete() {
+        let entry = entry("image/png;");
+        assert!(entry.handles_mime_glob("image/png"));
+        assert!(entry.handles_mime_glob("IMAGE/PNG"));
+        assert!(!entry.handles_mime_glob("image/jpeg"));
+    }
+
+    #[test]
+    fn wildcard_in_pattern() {
+        let entry = entry("image/png;");
+        assert!(entry.handles_mime_glob("image/*"));
+        assert!(entry.handles_mime_glob("*/png"));
+        assert!(!entry.handles_mime_glob("text/*"));
+    }
+
+    #[test]
+    fn wildcard_in_declared_type() {
+        let entry = entry("image/*;");
+        assert!(entry.handles_mime_glob("image/png"));
+        assert!(!entry.handles_mime_glob("text/plain"));
+        assert!(!entry.handles_mime_type("image/png"));
+    }
+
+    #[test]
+    fn wildcard_on_both_sides() {
+        let entry = entry("image/*;");
+        assert!(entry.handles_mime_glob("image/*"));
+        assert!(entry.handles_mime_glob("*/*"));
+        assert!(!entry.handles_mime_glob("text/*"));
+    }
+}
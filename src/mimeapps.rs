@@ -0,0 +1,228 @@
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::discovery::desktop_files_in;
+use crate::escape::split_list;
+use crate::{
+    ApplicationDesktopEntry, DesktopEntryType, DiscoveryOptions, DuplicateKeyPolicy, ParseOptions,
+    RawDesktopEntry, Result, applications_dirs, config_dirs, config_home, current_desktops,
+    data_dirs, data_home, find_entry_by_id_in, parse_raw,
+};
+
+/// The `mimeapps.list` files of the environment, most important first: for each of
+/// [`config_home`], [`config_dirs`], and the `applications` directories below [`data_home`] and
+/// [`data_dirs`], the `<desktop>-mimeapps.list` of each of the [`current_desktops`] followed by
+/// `mimeapps.list`.
+pub fn mimeapps_list_paths() -> Vec<PathBuf> {
+    let desktops = current_desktops();
+    std::iter::once(config_home())
+        .chain(config_dirs())
+        .chain(std::iter::once(data_home().join("applications")))
+        .chain(data_dirs().into_iter().map(|dir| dir.join("applications")))
+        .flat_map(|dir| {
+            desktops
+                .iter()
+                .map(|desktop| format!("{}-mimeapps.list", desktop.to_lowercase()))
+                .chain(std::iter::once("mimeapps.list".to_string()))
+                .map(move |file_name| dir.join(file_name))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The application that opens files of type `mime` by default, resolved like file managers do
+/// from the [`mimeapps_list_paths`] and the entries in the [`applications_dirs`].
+pub fn resolve_default_application(mime: &str) -> Result<Option<ApplicationDesktopEntry>> {
+    resolve_default_application_in(mime, &mimeapps_list_paths(), &applications_dirs())
+}
+
+/// Like [`resolve_default_application`], reading the `mimeapps_lists` files and searching
+/// `dirs` (both most important first) instead.
+///
+/// The first installed application listed under `[Default Applications]` wins, followed by
+/// those under `[Added Associations]` and finally by every entry declaring `mime` in
+/// `MimeType`. An application listed under `[Removed Associations]` is skipped from then on,
+/// that is for its own file and every less important one, and in the `MimeType` fallback.
+/// Missing and malformed lists are skipped. MIME types are compared as they are written,
+/// without resolving aliases or subclasses.
+pub fn resolve_default_application_in(
+    mime: &str,
+    mimeapps_lists: &[PathBuf],
+    dirs: &[PathBuf],
+) -> Result<Option<ApplicationDesktopEntry>> {
+    let mut lists = Vec::new();
+    for path in mimeapps_lists {
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                if let Ok(parsed) = parse_raw(&content, &list_parse_options()) {
+                    lists.push(parsed.groups);
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => (),
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    let installed = |id: &String| match find_entry_by_id_in(id, dirs) {
+        Ok(Some(DesktopEntryType::Application(entry))) if !entry.hidden_or_default() => Some(entry),
+        _ => None,
+    };
+
+    let mut removed = HashSet::new();
+    for group in ["Default Applications", "Added Associations"] {
+        removed.clear();
+        for list in &lists {
+            removed.extend(listed_ids(list, "Removed Associations", mime));
+            if let Some(entry) = listed_ids(list, group, mime)
+                .iter()
+                .filter(|id| !removed.contains(*id))
+                .find_map(installed)
+            {
+                return Ok(Some(entry));
+            }
+        }
+    }
+
+    for (id, _) in desktop_files_in(dirs, &DiscoveryOptions::default())? {
+        if removed.contains(&id) {
+            continue;
+        }
+        if let Some(entry) = installed(&id)
+            && entry.handles_mime_type(mime)
+        {
+            return Ok(Some(entry));
+        }
+    }
+
+    Ok(None)
+}
+
+// The lists are written by all sorts of tools, so they are read as forgivingly as possible.
+fn list_parse_options() -> ParseOptions {
+    ParseOptions {
+        lenient: true,
+        duplicate_key_policy: DuplicateKeyPolicy::First,
+        ..ParseOptions::default()
+    }
+}
+
+/// The desktop file IDs listed for `mime` in `group`, with the `.desktop` suffix.
+fn listed_ids(list: &RawDesktopEntry, group: &str, mime: &str) -> Vec<String> {
+    let Some(value) = list.get(group).and_then(|group| group.get(mime)) else {
+        return Vec::new();
+    };
+    split_list(value)
+        .into_iter()
+        .map(|id| {
+            if id.ends_with(".desktop") {
+                id
+            } else {
+                format!("{id}.desktop")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    /// Two lists and two applications directories, each most important first. `a` is in the
+    /// user directory, `b` and `c` in the system one, and `other` doesn't handle `text/plain`.
+    struct Fixture {
+        dir: TempDir,
+    }
+
+    impl Fixture {
+        fn new(user_list: &str, system_list: &str) -> Self {
+            let dir = TempDir::new();
+            dir.write("config/mimeapps.list", user_list);
+            dir.write("system/applications/mimeapps.list", system_list);
+            for (path, name, mime_type) in [
+                ("user/applications/a.desktop", "a", "text/plain;"),
+                ("system/applications/b.desktop", "b", "text/plain;"),
+                ("system/applications/c.desktop", "c", "text/plain;"),
+                ("system/applications/other.desktop", "other", "image/png;"),
+            ] {
+                dir.write(
+                    path,
+                    &format!(
+                        "[Desktop Entry]\nType=Application\nName={name}\nExec={name} %f\n\
+                        MimeType={mime_type}\n"
+                    ),
+                );
+            }
+            Fixture { dir }
+        }
+
+        fn resolve(&self, mime: &str) -> Option<String> {
+            let root = self.dir.path();
+            let lists = [
+                root.join("config/mimeapps.list"),
+                root.join("system/applications/mimeapps.list"),
+            ];
+            let dirs = [
+                root.join("user/applications"),
+                root.join("system/applications"),
+            ];
+            resolve_default_application_in(mime, &lists, &dirs)
+                .unwrap()
+                .map(|entry| entry.name)
+        }
+    }
+
+    #[test]
+    fn default_applications_come_before_added_associations() {
+        let fixture = Fixture::new(
+            "[Added Associations]\ntext/plain=a.desktop;\n",
+            "[Default Applications]\ntext/plain=c.desktop;\n",
+        );
+        assert_eq!(fixture.resolve("text/plain").as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn listed_applications_that_are_not_installed_are_skipped() {
+        let fixture = Fixture::new(
+            "[Default Applications]\ntext/plain=missing.desktop;\n\
+            [Added Associations]\ntext/plain=missing;b;\n",
+            "",
+        );
+        assert_eq!(fixture.resolve("text/plain").as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn mime_type_is_the_last_resort() {
+        let fixture = Fixture::new("", "");
+        assert_eq!(fixture.resolve("text/plain").as_deref(), Some("a"));
+        assert_eq!(fixture.resolve("image/png").as_deref(), Some("other"));
+        assert_eq!(fixture.resolve("video/mp4"), None);
+    }
+
+    #[test]
+    fn removed_associations_apply_to_their_own_and_less_important_files() {
+        let fixture = Fixture::new(
+            "[Removed Associations]\ntext/plain=a.desktop;b.desktop;\n",
+            "[Default Applications]\ntext/plain=a.desktop;\n",
+        );
+        // Also skipped in the MimeType fallback.
+        assert_eq!(fixture.resolve("text/plain").as_deref(), Some("c"));
+
+        let fixture = Fixture::new(
+            "[Default Applications]\ntext/plain=a.desktop;\n\
+            [Removed Associations]\ntext/plain=a.desktop;\n",
+            "",
+        );
+        assert_eq!(fixture.resolve("text/plain").as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn removed_associations_do_not_apply_to_more_important_files() {
+        let fixture = Fixture::new(
+            "[Default Applications]\ntext/plain=b.desktop;\n",
+            "[Removed Associations]\ntext/plain=b.desktop;\n",
+        );
+        assert_eq!(fixture.resolve("text/plain").as_deref(), Some("b"));
+    }
+}
@@ -0,0 +1,133 @@
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::Path;
+
+use crate::exec::{FieldValues, expand_field_codes, parse_exec_bytes};
+use crate::{Error, Result};
+
+/// The launch-related values of a file's `[Desktop Entry]` group, read as bytes, as they appear
+/// in the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[allow(unused)]
+pub struct OsLaunchValues {
+    pub exec: Option<OsString>,
+    pub path: Option<OsString>,
+    /// The untranslated `Name`, which `%c` expands to.
+    pub name: Option<OsString>,
+    pub icon: Option<OsString>,
+}
+
+impl OsLaunchValues {
+    /// [`ApplicationDesktopEntry::build_command`](crate::ApplicationDesktopEntry::build_command)
+    /// for values that aren't valid UTF-8, expanding the same field codes. `%c` takes the
+    /// untranslated `Name`, since translations aren't read.
+    pub fn build_command(&self, files: &[OsString]) -> Result<Vec<OsString>> {
+        let exec = self
+            .exec
+            .as_deref()
+            .ok_or(Error::FormatError("Entry has no Exec key".to_string()))?;
+
+        let values = FieldValues {
+            files: files.iter().map(|file| file.as_bytes()).collect(),
+            icon: self.icon.as_deref().map(OsStr::as_bytes),
+            name: self.name.as_deref().map_or(&[], OsStr::as_bytes),
+        };
+        let command = expand_field_codes(parse_exec_bytes(exec.as_bytes())?, &values)?;
+        Ok(command.into_iter().map(OsString::from_vec).collect())
+    }
+}
+
+/// Reads `Exec`, `Path`, `Name` and `Icon` from the file at `path` without requiring it to be
+/// valid UTF-8, which the other parse functions do. For files with file names in a legacy
+/// encoding, where the regular parse fails.
+pub fn parse_launch_values_os<P: AsRef<Path>>(path: P) -> Result<OsLaunchValues> {
+    let content = std::fs::read(path)?;
+    let mut values = OsLaunchValues::default();
+    let mut in_main_group = false;
+
+    for line in content.split(|&byte| byte == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.starts_with(b"[") && line.ends_with(b"]") {
+            in_main_group = line == b"[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group {
+            continue;
+        }
+
+        let Some(separator) = line.iter().position(|&byte| byte == b'=') else {
+            continue;
+        };
+        let value = OsString::from_vec(line[separator + 1..].trim_ascii().to_vec());
+        match line[..separator].trim_ascii() {
+            b"Exec" => values.exec = Some(value),
+            b"Path" => values.path = Some(value),
+            b"Name" => values.name = Some(value),
+            b"Icon" => values.icon = Some(value),
+            _ => (),
+        }
+    }
+
+    Ok(values)
+}
+
+/// [`parse_exec`](crate::parse_exec) for values that aren't valid UTF-8. The escape sequences
+/// and quoting are all ASCII, so the bytes in between are passed through untouched.
+pub fn parse_exec_os(exec: &OsStr) -> Result<Vec<OsString>> {
+    let args = parse_exec_bytes(exec.as_bytes())?;
+    Ok(args.into_iter().map(OsString::from_vec).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn builds_a_command_from_bytes() {
+        let dir = TempDir::new();
+        let path = dir.write("app.desktop", "");
+        std::fs::write(
+            &path,
+            b"[Desktop Entry]\nType=Application\nName=Caf\xe9\\sApp\nIcon=caf\xe9\n\
+            Exec=/opt/caf\xe9/app --name=%c %i %F\n",
+        )
+        .unwrap();
+
+        let values = parse_launch_values_os(&path).unwrap();
+        let files = [
+            OsString::from_vec(b"/tmp/\xff.txt".to_vec()),
+            OsString::from("b"),
+        ];
+        let command = values.build_command(&files).unwrap();
+        let expected: [&[u8]; 6] = [
+            b"/opt/caf\xe9/app",
+            b"--name=Caf\xe9 App",
+            b"--icon",
+            b"caf\xe9",
+            b"/tmp/\xff.txt",
+            b"b",
+        ];
+        assert_eq!(
+            command,
+            expected.map(|arg| OsString::from_vec(arg.to_vec()))
+        );
+    }
+
+    #[test]
+    fn file_codes_follow_the_utf8_builder() {
+        let values = OsLaunchValues {
+            exec: Some(OsString::from("app %f %%")),
+            ..OsLaunchValues::default()
+        };
+        assert_eq!(values.build_command(&[]).unwrap(), ["app", "%"]);
+        assert!(
+            OsLaunchValues {
+                exec: Some(OsString::from("app x%F")),
+                ..OsLaunchValues::default()
+            }
+            .build_command(&[])
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    DesktopEntryType, DuplicateKeyPolicy, ParseOptions, Severity, Warning,
+    parse_desktop_entry_raw_str_with,
+};
+
+/// A problem found by [`parse_partial`].
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct Diagnostic {
+    /// [`Severity::Error`] for lines that had to be dropped and entries that couldn't be built,
+    /// [`Severity::Warning`] for problems the parser recovered from.
+    pub severity: Severity,
+    /// The 1-based line the problem was found on, when it is tied to one.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl From<Warning> for Diagnostic {
+    fn from(warning: Warning) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            line: warning.line,
+            message: warning.message,
+        }
+    }
+}
+
+/// Parses as much of `content` as possible, for editors that show an entry while it is being
+/// written. Parsing is lenient and keeps the first value of a repeated key, and lines that are
+/// neither a group header, a comment nor a `key=value` pair are dropped with a diagnostic.
+/// The entry is `None` when what remains still doesn't make an entry, for instance because
+/// `Type` is missing. Diagnostics are ordered by line, those not tied to one last.
+pub fn parse_partial(content: &str) -> (Option<DesktopEntryType>, Vec<Diagnostic>) {
+    let options = ParseOptions {
+        lenient: true,
+        assume_desktop_entry_group: true,
+        duplicate_key_policy: DuplicateKeyPolicy::First,
+        ..ParseOptions::default()
+    };
+    let mut diagnostics = Vec::new();
+
+    // Dropped lines are blanked rather than removed, so later line numbers stay right.
+    let cleaned: String = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let trimmed = line.trim();
+            let is_header = trimmed.starts_with('[') && trimmed.ends_with(']');
+            if line.is_empty() || line.starts_with('#') || is_header || line.contains('=') {
+                format!("{line}\n")
+            } else {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    line: Some(index + 1),
+                    message: "Entry not key/value, line ignored".to_string(),
+                });
+                "\n".to_string()
+            }
+        })
+        .collect();
+
+    let entry = parse_desktop_entry_raw_str_with(&cleaned, &options).and_then(|(raw, warnings)| {
+        diagnostics.extend(warnings.into_iter().map(Diagnostic::from));
+        DesktopEntryType::from_raw_with(&raw, &options)
+    });
+
+    let entry = match entry {
+        Ok((entry, warnings)) => {
+            diagnostics.extend(warnings.into_iter().map(Diagnostic::from));
+            Some(entry)
+        }
+        Err(error) => {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                line: None,
+                message: error.to_string(),
+            });
+            None
+        }
+    };
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.line.unwrap_or(usize::MAX));
+    (entry, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropped_lines_keep_later_line_numbers() {
+        let (entry, diagnostics) = parse_partial(
+            "[Desktop Entry]\nType=Application\nhalf-typed\nName=First\nName=Second\nExec=app\n",
+        );
+        let Some(DesktopEntryType::Application(entry)) = entry else {
+            panic!("not an application");
+        };
+        assert_eq!(entry.name, "First");
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.line))
+            .collect();
+        assert_eq!(
+            found,
+            [(Severity::Error, Some(3)), (Severity::Warning, Some(5))]
+        );
+    }
+
+    #[test]
+    fn missing_type_gives_no_entry_and_an_error() {
+        let (entry, diagnostics) = parse_partial("[Desktop Entry]\nName=App\n");
+        assert!(entry.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, None);
+        assert!(diagnostics[0].message.contains("type"), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn missing_header_is_assumed() {
+        let (entry, diagnostics) = parse_partial("Type=Application\nName=App\n");
+        assert!(matches!(entry, Some(DesktopEntryType::Application(_))));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].line, Some(1));
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::locale::locale_candidates;
+use crate::{
+    Error, ParseOptions, RawDesktopEntry, Result, SIGNIFICANT_WHITESPACE_KEYS, Warning, bool_value,
+    list_value, parse_raw,
+};
+
+/// The groups of a file together with the structure the plain [`RawDesktopEntry`] map loses:
+/// the order groups appear in and which of them are declared more than once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(unused)]
+pub struct RawDesktopFile {
+    groups: RawDesktopEntry,
+    order: Vec<String>,
+    duplicate_groups: Vec<String>,
+    repeated_values: HashMap<(String, String), Vec<String>>,
+}
+
+impl RawDesktopFile {
+    /// The group names in the order they first appear in the file.
+    pub fn group_names(&self) -> Vec<&str> {
+        self.order.iter().map(String::as_str).collect()
+    }
+
+    /// The groups whose header appears more than once. The spec forbids this; the parser merges
+    /// the keys of every occurrence into one group.
+    pub fn duplicate_groups(&self) -> &[String] {
+        &self.duplicate_groups
+    }
+
+    /// Every value of `key` in `group`, in file order. Only keys parsed with
+    /// [`DuplicateKeyPolicy::CollectAll`](crate::DuplicateKeyPolicy::CollectAll) can have more
+    /// than one.
+    pub fn values(&self, group: &str, key: &str) -> Vec<&str> {
+        match self
+            .repeated_values
+            .get(&(group.to_string(), key.to_string()))
+        {
+            Some(values) => values.iter().map(String::as_str).collect(),
+            None => self
+                .get(group)
+                .and_then(|group| group.get(key))
+                .map(String::as_str)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    pub fn groups(&self) -> &RawDesktopEntry {
+        &self.groups
+    }
+
+    pub fn get(&self, group: &str) -> Option<&HashMap<String, String>> {
+        self.groups.get(group)
+    }
+
+    /// `group` with typed access to its values, e.g. for action or vendor groups that the
+    /// entry types don't cover.
+    pub fn group(&self, name: &str) -> Option<GroupView<'_>> {
+        let (name, entries) = self.groups.get_key_value(name)?;
+        Some(GroupView { name, entries })
+    }
+
+    pub fn into_groups(self) -> RawDesktopEntry {
+        self.groups
+    }
+
+    /// Parses `content` with `options`, returning the warnings recorded along the way.
+    pub fn parse_with(content: &str, options: &ParseOptions) -> Result<(Self, Vec<Warning>)> {
+        let parsed = parse_raw(content, options)?;
+        let file = RawDesktopFile {
+            groups: parsed.groups,
+            order: parsed.order,
+            duplicate_groups: parsed.duplicate_groups,
+            repeated_values: parsed.repeated_values,
+        };
+        Ok((file, parsed.warnings))
+    }
+}
+
+/// A group of a [`RawDesktopFile`], read with the same rules as the fields of the entry types.
+/// Like those, strings are returned as they appear in the file, escape sequences included.
+#[derive(Debug, Clone, Copy)]
+#[allow(unused)]
+pub struct GroupView<'a> {
+    name: &'a str,
+    entries: &'a HashMap<String, String>,
+}
+
+impl<'a> GroupView<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn entries(&self) -> &'a HashMap<String, String> {
+        self.entries
+    }
+
+    /// The value of `key`. A whitespace-only value counts as absent, except for the keys in
+    /// [`SIGNIFICANT_WHITESPACE_KEYS`].
+    pub fn get_str(&self, key: &str) -> Option<&'a str> {
+        self.entries
+            .get(key)
+            .map(String::as_str)
+            .filter(|value| !value.trim().is_empty() || SIGNIFICANT_WHITESPACE_KEYS.contains(&key))
+    }
+
+    /// The value of `key` as a boolean, where anything but `true` is `false`.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        bool_value(self.entries, key)
+    }
+
+    /// The value of `key` split with [`split_list`](crate::split_list).
+    pub fn get_list(&self, key: &str) -> Option<Vec<String>> {
+        list_value(self.entries, key)
+    }
+
+    /// The translation of `key` best matching `locale`, falling back to the untranslated value
+    /// like [`crate::ApplicationDesktopEntry::localized_name`] does.
+    pub fn get_locale_str(&self, key: &str, locale: &str) -> Option<&'a str> {
+        locale_candidates(locale)
+            .iter()
+            .find_map(|candidate| self.entries.get(&format!("{key}[{candidate}]")))
+            .map(String::as_str)
+            .or_else(|| self.get_str(key))
+    }
+}
+
+impl FromStr for RawDesktopFile {
+    type Err = Error;
+
+    fn from_str(content: &str) -> Result<Self> {
+        RawDesktopFile::parse_with(content, &ParseOptions::keeping_last_value())
+            .map(|(file, _)| file)
+    }
+}
+
+/// Builds a group from `(key, value)` pairs, with values as they are to appear in a file. A
+/// later pair replaces an earlier one with the same key.
+pub fn group_from_pairs<K: Into<String>, V: Into<String>>(
+    pairs: impl IntoIterator<Item = (K, V)>,
+) -> HashMap<String, String> {
+    pairs
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into()))
+        .collect()
+}
+
+/// Builds a [`RawDesktopEntry`] from `(name, group)` pairs, e.g. groups made with
+/// [`group_from_pairs`], ready to be typed with `try_from`.
+pub fn raw_from_groups<N: Into<String>>(
+    groups: impl IntoIterator<Item = (N, HashMap<String, String>)>,
+) -> RawDesktopEntry {
+    groups
+        .into_iter()
+        .map(|(name, group)| (name.into(), group))
+        .collect()
+}
+
+pub fn parse_desktop_file_raw<P: AsRef<Path>>(path: P) -> Result<RawDesktopFile> {
+    std::fs::read_to_string(path)?.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DuplicateKeyPolicy, ParseOptions, RawDesktopFile};
+
+    const REPEATED: &str = "[Desktop Entry]\nType=Application\nName=A\nExec=a\nName=B\nName=C\n";
+
+    fn parse(policy: DuplicateKeyPolicy) -> crate::Result<(RawDesktopFile, Vec<crate::Warning>)> {
+        let options = ParseOptions {
+            duplicate_key_policy: policy,
+            ..ParseOptions::default()
+        };
+        RawDesktopFile::parse_with(REPEATED, &options)
+    }
+
+    #[test]
+    fn error_policy_fails() {
+        let error = parse(DuplicateKeyPolicy::Error).unwrap_err();
+        assert!(error.to_string().contains("on line 5"));
+    }
+
+    #[test]
+    fn first_policy_keeps_the_first_value() {
+        let (file, warnings) = parse(DuplicateKeyPolicy::First).unwrap();
+        assert_eq!(file.get("Desktop Entry").unwrap()["Name"], "A");
+        assert_eq!(file.values("Desktop Entry", "Name"), ["A"]);
+        let lines: Vec<_> = warnings.iter().map(|warning| warning.line).collect();
+        assert_eq!(lines, [Some(5), Some(6)]);
+    }
+
+    #[test]
+    fn last_policy_keeps_the_last_value() {
+        let (file, warnings) = parse(DuplicateKeyPolicy::Last).unwrap();
+        assert_eq!(file.get("Desktop Entry").unwrap()["Name"], "C");
+        assert_eq!(file.values("Desktop Entry", "Name"), ["C"]);
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn collect_all_policy_keeps_every_value() {
+        let (file, warnings) = parse(DuplicateKeyPolicy::CollectAll).unwrap();
+        assert_eq!(file.get("Desktop Entry").unwrap()["Name"], "C");
+        assert_eq!(file.values("Desktop Entry", "Name"), ["A", "B", "C"]);
+        assert_eq!(file.values("Desktop Entry", "Exec"), ["a"]);
+        assert!(file.values("Desktop Entry", "Icon").is_empty());
+        assert!(file.values("Missing", "Name").is_empty());
+        assert!(warnings.is_empty());
+    }
+}
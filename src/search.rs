@@ -0,0 +1,44 @@
+use crate::ApplicationDesktopEntry;
+
+impl ApplicationDesktopEntry {
+    /// Whether `query` occurs in the name, generic name, keywords or comment translated for
+    /// `locale`. This is a plain case-insensitive substring match, not a fuzzy one, so callers
+    /// can add their own ranking on top.
+    pub fn matches_search(&self, query: &str, locale: &str) -> bool {
+        self.search_score(query, locale) > 0
+    }
+
+    /// How well the entry matches `query`, for ranking search results; `0` when it doesn't
+    /// match at all. The score is that of the best matching field, each compared like
+    /// [`Self::matches_search`] does:
+    ///
+    /// - 100 when the name starts with `query`,
+    /// - 80 when the name contains it,
+    /// - 60 when one of the keywords contains it,
+    /// - 40 when the generic name contains it,
+    /// - 20 when the comment contains it.
+    pub fn search_score(&self, query: &str, locale: &str) -> u32 {
+        let query = query.to_lowercase();
+        let contains = |field: &str| field.to_lowercase().contains(&query);
+
+        let name = self.localized_name(locale).to_lowercase();
+        if name.starts_with(&query) {
+            100
+        } else if name.contains(&query) {
+            80
+        } else if self
+            .localized_keywords(locale)
+            .unwrap_or_default()
+            .iter()
+            .any(|keyword| contains(keyword))
+        {
+            60
+        } else if self.localized_generic_name(locale).is_some_and(contains) {
+            40
+        } else if self.localized_comment(locale).is_some_and(contains) {
+            20
+        } else {
+            0
+        }
+    }
+}
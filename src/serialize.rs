@@ -0,0 +1,459 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use crate::edit::write_atomically;
+use crate::escape::{join_list, protect_whitespace};
+use crate::{
+    ApplicationDesktopEntry, DesktopEntryType, DirectoryDesktopEntry, LinkDesktopEntry,
+    RawDesktopEntry, Result,
+};
+
+/// How [`SerializeOptions`] orders the groups of a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(unused)]
+pub enum GroupOrder {
+    /// `[Desktop Entry]` first, followed by the other groups in the order the entry defines
+    /// them, such as actions in the order `Actions` lists them. Typed entries don't remember
+    /// the order of the file they were parsed from, so this needn't match it;
+    /// [`RawDesktopFile::group_names`](crate::RawDesktopFile::group_names) has that order.
+    #[default]
+    EntryOrder,
+    /// `[Desktop Entry]` first, followed by the other groups sorted by name.
+    Sorted,
+}
+
+#[derive(Debug, Clone, Default)]
+#[allow(unused)]
+pub struct SerializeOptions {
+    /// Write `Type`, `Name` with its translations and `URL` first and the remaining keys of each
+    /// group sorted by name, instead of in the order the spec lists them.
+    pub sort_keys: bool,
+    pub group_order: GroupOrder,
+}
+
+/// Keys that [`SerializeOptions::sort_keys`] keeps ahead of the alphabetical ones.
+const LEADING_KEYS: &[&str] = &["Type", "Name", "URL"];
+
+/// The keys of a group in the order they are written, with their values as they appear in
+/// the file.
+type Group = Vec<(String, String)>;
+
+// Typed fields hold values as they appear in the file, escape sequences included, so values
+// are written out verbatim. `;` needs no escaping outside of list fields, and `=` never does
+// since only the first `=` on a line separates the key from the value. The only characters
+// encoded are the ones parsing would otherwise lose: line breaks, and whitespace at either end
+// of the value, which the parser trims.
+fn write_string(out: &mut Group, key: &str, value: Option<&str>) {
+    if let Some(value) = value {
+        out.push((key.to_string(), protect_whitespace(value)));
+    }
+}
+
+fn write_list(out: &mut Group, key: &str, value: Option<&[String]>) {
+    write_string(out, key, value.map(join_list).as_deref());
+}
+
+/// Writes the translations of `key` as `key[locale]`, sorted by locale.
+fn write_localized(out: &mut Group, key: &str, translations: &BTreeMap<String, String>) {
+    for (locale, value) in translations {
+        write_string(out, &format!("{key}[{locale}]"), Some(value));
+    }
+}
+
+fn write_bool(out: &mut Group, key: &str, value: Option<bool>) {
+    write_string(
+        out,
+        key,
+        value.map(|value| if value { "true" } else { "false" }),
+    );
+}
+
+fn write_extensions(out: &mut Group, extensions: &BTreeMap<String, String>) {
+    for (key, value) in extensions {
+        write_string(out, key, Some(value));
+    }
+}
+
+fn write_header(out: &mut Group, entry_type: &str) {
+    write_string(out, "Type", Some(entry_type));
+}
+
+fn render(mut groups: Vec<(String, Group)>, options: &SerializeOptions) -> String {
+    if options.group_order == GroupOrder::Sorted {
+        groups.sort_by(|(left, _), (right, _)| {
+            (left != "Desktop Entry", left).cmp(&(right != "Desktop Entry", right))
+        });
+    }
+    if options.sort_keys {
+        for (_, group) in &mut groups {
+            group.sort_by_key(|(key, _)| {
+                // Translations stay next to the key they translate, `Name[de]` after `Name`.
+                let base = key.split_once('[').map_or(key.as_str(), |(base, _)| base);
+                let leading = LEADING_KEYS.iter().position(|leading| *leading == base);
+                (leading.unwrap_or(LEADING_KEYS.len()), key.clone())
+            });
+        }
+    }
+
+    let mut out = String::new();
+    for (index, (name, group)) in groups.into_iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        out.push('[');
+        out.push_str(&name);
+        out.push_str("]\n");
+        for (key, value) in group {
+            out.push_str(&key);
+            out.push('=');
+            out.push_str(&value);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn into_raw(groups: Vec<(String, Group)>) -> RawDesktopEntry {
+    groups
+        .into_iter()
+        .map(|(name, group)| (name, group.into_iter().collect::<HashMap<_, _>>()))
+        .collect()
+}
+
+impl ApplicationDesktopEntry {
+    fn groups(&self) -> Vec<(String, Group)> {
+        let mut out = Group::new();
+        write_header(&mut out, "Application");
+        write_string(&mut out, "Version", self.version.as_deref());
+        write_string(&mut out, "Name", Some(&self.name));
+        write_localized(&mut out, "Name", &self.name_localized);
+        write_string(&mut out, "GenericName", self.generic_name.as_deref());
+        write_localized(&mut out, "GenericName", &self.generic_name_localized);
+        write_bool(&mut out, "NoDisplay", self.no_display);
+        write_string(&mut out, "Comment", self.comment.as_deref());
+        write_localized(&mut out, "Comment", &self.comment_localized);
+        write_string(&mut out, "Icon", self.icon.as_deref());
+        write_bool(&mut out, "Hidden", self.hidden);
+        write_list(&mut out, "OnlyShowIn", self.only_show_in.as_deref());
+        write_list(&mut out, "NotShowIn", self.not_show_in.as_deref());
+        write_string(&mut out, "TryExec", self.try_exec.as_deref());
+        write_string(&mut out, "Exec", self.exec.as_deref());
+        write_string(&mut out, "Path", self.path.as_deref());
+        write_bool(&mut out, "Terminal", self.terminal);
+        write_list(&mut out, "Actions", self.actions.as_deref());
+        write_list(&mut out, "MimeType", self.mime_type.as_deref());
+        write_list(&mut out, "Categories", self.categories.as_deref());
+        write_list(&mut out, "Keywords", self.keywords.as_deref());
+        for (locale, keywords) in &self.keywords_localized {
+            write_list(&mut out, &format!("Keywords[{locale}]"), Some(keywords));
+        }
+        write_bool(&mut out, "StartupNotify", self.startup_notify);
+        write_string(&mut out, "StartupWMClass", self.startup_wm_class.as_deref());
+        write_bool(
+            &mut out,
+            "PrefersNonDefaultGPU",
+            self.prefers_non_default_gpu,
+        );
+        write_bool(&mut out, "SingleMainWindow", self.single_main_window);
+        write_extensions(&mut out, &self.extensions);
+
+        let mut groups = vec![("Desktop Entry".to_string(), out)];
+        for action in &self.desktop_actions {
+            let mut out = Group::new();
+            write_string(&mut out, "Name", Some(&action.name));
+            write_localized(&mut out, "Name", &action.name_localized);
+            write_string(&mut out, "Icon", action.icon.as_deref());
+            write_string(&mut out, "Exec", action.exec.as_deref());
+            groups.push((format!("Desktop Action {}", action.id), out));
+        }
+        groups
+    }
+
+    pub fn to_desktop_string(&self) -> String {
+        self.to_desktop_string_with(&SerializeOptions::default())
+    }
+
+    pub fn to_desktop_string_with(&self, options: &SerializeOptions) -> String {
+        render(self.groups(), options)
+    }
+
+    /// The raw groups that parsing [`Self::to_desktop_string`] would produce.
+    pub fn to_raw(&self) -> RawDesktopEntry {
+        into_raw(self.groups())
+    }
+
+    /// Writes [`Self::to_desktop_string`] to `path` like [`DesktopEntryType::write_to_file`].
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_atomically(path.as_ref(), &self.to_desktop_string())?;
+        Ok(())
+    }
+}
+
+impl LinkDesktopEntry {
+    fn groups(&self) -> Vec<(String, Group)> {
+        let mut out = Group::new();
+        write_header(&mut out, "Link");
+        write_string(&mut out, "Version", self.version.as_deref());
+        write_string(&mut out, "Name", Some(&self.name));
+        write_localized(&mut out, "Name", &self.name_localized);
+        write_string(&mut out, "GenericName", self.generic_name.as_deref());
+        write_localized(&mut out, "GenericName", &self.generic_name_localized);
+        write_bool(&mut out, "NoDisplay", self.no_display);
+        write_string(&mut out, "Comment", self.comment.as_deref());
+        write_localized(&mut out, "Comment", &self.comment_localized);
+        write_string(&mut out, "Icon", self.icon.as_deref());
+        write_bool(&mut out, "Hidden", self.hidden);
+        write_list(&mut out, "OnlyShowIn", self.only_show_in.as_deref());
+        write_list(&mut out, "NotShowIn", self.not_show_in.as_deref());
+        write_string(&mut out, "URL", self.url.as_deref());
+        write_extensions(&mut out, &self.extensions);
+        vec![("Desktop Entry".to_string(), out)]
+    }
+
+    pub fn to_desktop_string(&self) -> String {
+        self.to_desktop_string_with(&SerializeOptions::default())
+    }
+
+    pub fn to_desktop_string_with(&self, options: &SerializeOptions) -> String {
+        render(self.groups(), options)
+    }
+
+    /// The raw groups that parsing [`Self::to_desktop_string`] would produce.
+    pub fn to_raw(&self) -> RawDesktopEntry {
+        into_raw(self.groups())
+    }
+
+    /// Writes [`Self::to_desktop_string`] to `path` like [`DesktopEntryType::write_to_file`].
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_atomically(path.as_ref(), &self.to_desktop_string())?;
+        Ok(())
+    }
+}
+
+impl DirectoryDesktopEntry {
+    fn groups(&self) -> Vec<(String, Group)> {
+        let mut out = Group::new();
+        write_header(&mut out, "Directory");
+        write_string(&mut out, "Version", self.version.as_deref());
+        write_string(&mut out, "Name", Some(&self.name));
+        write_localized(&mut out, "Name", &self.name_localized);
+        write_string(&mut out, "GenericName", self.generic_name.as_deref());
+        write_localized(&mut out, "GenericName", &self.generic_name_localized);
+        write_bool(&mut out, "NoDisplay", self.no_display);
+        write_string(&mut out, "Comment", self.comment.as_deref());
+        write_localized(&mut out, "Comment", &self.comment_localized);
+        write_string(&mut out, "Icon", self.icon.as_deref());
+        write_bool(&mut out, "Hidden", self.hidden);
+        write_list(&mut out, "OnlyShowIn", self.only_show_in.as_deref());
+        write_list(&mut out, "NotShowIn", self.not_show_in.as_deref());
+        write_extensions(&mut out, &self.extensions);
+        vec![("Desktop Entry".to_string(), out)]
+    }
+
+    pub fn to_desktop_string(&self) -> String {
+        self.to_desktop_string_with(&SerializeOptions::default())
+    }
+
+    pub fn to_desktop_string_with(&self, options: &SerializeOptions) -> String {
+        render(self.groups(), options)
+    }
+
+    /// The raw groups that parsing [`Self::to_desktop_string`] would produce.
+    pub fn to_raw(&self) -> RawDesktopEntry {
+        into_raw(self.groups())
+    }
+
+    /// Writes [`Self::to_desktop_string`] to `path` like [`DesktopEntryType::write_to_file`].
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_atomically(path.as_ref(), &self.to_desktop_string())?;
+        Ok(())
+    }
+}
+
+impl DesktopEntryType {
+    /// Serializes the entry as the contents of a `.desktop` file. Parsing the result yields the
+    /// same entry again for any entry that was parsed with the default [`crate::ParseOptions`].
+    ///
+    /// Fields are expected to hold escaped file text, like parsing produces. A value that
+    /// parsing can't produce, with a line break or whitespace at either end, is written with
+    /// escape sequences instead; it keeps its decoded meaning, but comes back escaped, so
+    /// `"  Foo"` is read back as `"\s Foo"`. Use [`crate::escape_value`] for arbitrary text.
+    pub fn to_desktop_string(&self) -> String {
+        self.to_desktop_string_with(&SerializeOptions::default())
+    }
+
+    /// [`Self::to_desktop_string`] with control over the order of groups and keys, e.g. for
+    /// reproducible output.
+    pub fn to_desktop_string_with(&self, options: &SerializeOptions) -> String {
+        match self {
+            DesktopEntryType::Application(entry) => entry.to_desktop_string_with(options),
+            DesktopEntryType::Link(entry) => entry.to_desktop_string_with(options),
+            DesktopEntryType::Directory(entry) => entry.to_desktop_string_with(options),
+        }
+    }
+
+    /// Converts the entry back into raw groups, the reverse of typing a [`RawDesktopEntry`]
+    /// with `try_from`.
+    pub fn to_raw(&self) -> RawDesktopEntry {
+        match self {
+            DesktopEntryType::Application(entry) => entry.to_raw(),
+            DesktopEntryType::Link(entry) => entry.to_raw(),
+            DesktopEntryType::Directory(entry) => entry.to_raw(),
+        }
+    }
+
+    /// Writes [`Self::to_desktop_string`] to `path`, replacing any existing file atomically:
+    /// the contents go to a temporary file in the same directory, which is synced to disk and
+    /// renamed over `path`. Readers see either the old file or the complete new one, even if
+    /// the process dies while writing. An existing file keeps its permissions.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_atomically(path.as_ref(), &self.to_desktop_string())?;
+        Ok(())
+    }
+}
+
+/// Formats the entry as [`DesktopEntryType::to_desktop_string`] does, the counterpart to its
+/// [`FromStr`](std::str::FromStr) implementation.
+impl fmt::Display for DesktopEntryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_desktop_string())
+    }
+}
+
+impl From<&DesktopEntryType> for RawDesktopEntry {
+    fn from(entry: &DesktopEntryType) -> Self {
+        entry.to_raw()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GroupOrder, SerializeOptions};
+    use crate::{ApplicationDesktopEntry, DesktopEntryType, unescape_value};
+
+    const ALPHABET: [char; 9] = ['a', ' ', '\t', '\r', '\n', '\\', ';', '=', 'é'];
+
+    /// Every string of up to three characters from [`ALPHABET`].
+    fn values() -> Vec<String> {
+        let mut values = vec![String::new()];
+        let mut previous = values.clone();
+        for _ in 0..3 {
+            previous = previous
+                .iter()
+                .flat_map(|value| ALPHABET.iter().map(move |c| format!("{value}{c}")))
+                .collect();
+            values.extend(previous.iter().cloned());
+        }
+        values
+    }
+
+    fn application(entry: DesktopEntryType) -> ApplicationDesktopEntry {
+        match entry {
+            DesktopEntryType::Application(entry) => entry,
+            other => panic!("not an application: {other:?}"),
+        }
+    }
+
+    fn round_trip(entry: &ApplicationDesktopEntry) -> ApplicationDesktopEntry {
+        let written = DesktopEntryType::Application(entry.clone()).to_desktop_string();
+        application(written.parse().unwrap())
+    }
+
+    #[test]
+    fn parsed_name_comment_and_exec_round_trip() {
+        for value in values().iter().filter(|value| !value.contains('\n')) {
+            let content = format!(
+                "[Desktop Entry]\nType=Application\nName={value}\nComment={value}\nExec={value}\n"
+            );
+            let parsed = application(content.parse().unwrap());
+            assert_eq!(round_trip(&parsed), parsed, "value {value:?}");
+        }
+    }
+
+    #[test]
+    fn built_name_comment_and_exec_keep_their_meaning() {
+        for value in values() {
+            let Ok(decoded) = unescape_value(&value) else {
+                continue;
+            };
+            let entry = ApplicationDesktopEntry::builder(value.as_str())
+                .comment(value.as_str())
+                .exec(value.as_str())
+                .build();
+            let read_back = round_trip(&entry);
+            assert_eq!(
+                unescape_value(&read_back.name).unwrap(),
+                decoded,
+                "{value:?}"
+            );
+            assert_eq!(
+                unescape_value(read_back.comment.as_deref().unwrap()).unwrap(),
+                decoded,
+                "{value:?}"
+            );
+            assert_eq!(
+                unescape_value(read_back.exec.as_deref().unwrap()).unwrap(),
+                decoded,
+                "{value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn inner_tab_is_kept_verbatim() {
+        let parsed = application(
+            "[Desktop Entry]\nType=Application\nName=a\tb\n"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(parsed.name, "a\tb");
+        assert_eq!(round_trip(&parsed), parsed);
+    }
+
+    #[test]
+    fn leading_spaces_are_written_escaped() {
+        let entry = ApplicationDesktopEntry::builder("  Foo;Bar").build();
+        let read_back = round_trip(&entry);
+        assert_eq!(read_back.name, "\\s Foo;Bar");
+        assert_eq!(read_back.name_decoded().unwrap(), "  Foo;Bar");
+    }
+
+    #[test]
+    fn groups_follow_the_entry_or_are_sorted() {
+        let entry: DesktopEntryType = "[Desktop Entry]\nType=Application\nName=App\nExec=app\n\
+            Actions=zoom;about;\n[Desktop Action about]\nName=About\n[Desktop Action zoom]\n\
+            Name=Zoom\n"
+            .parse()
+            .unwrap();
+        let headers = |group_order| {
+            let options = SerializeOptions {
+                group_order,
+                ..SerializeOptions::default()
+            };
+            entry
+                .to_desktop_string_with(&options)
+                .lines()
+                .filter(|line| line.starts_with('['))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            headers(GroupOrder::EntryOrder),
+            [
+                "[Desktop Entry]",
+                "[Desktop Action zoom]",
+                "[Desktop Action about]"
+            ]
+        );
+        assert_eq!(
+            headers(GroupOrder::Sorted),
+            [
+                "[Desktop Entry]",
+                "[Desktop Action about]",
+                "[Desktop Action zoom]"
+            ]
+        );
+    }
+}
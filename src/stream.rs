@@ -0,0 +1,79 @@
+use std::io::{BufRead, BufReader, Read};
+
+use crate::{DesktopEntryType, Result, parse_desktop_entry_raw_str};
+
+/// Parses a stream of concatenated desktop entries, calling `on_entry` for each one as soon as
+/// it is complete. Every `[Desktop Entry]` header starts a new entry, and the groups following
+/// it (such as action groups) belong to that entry.
+///
+/// Only one entry is held in memory at a time. A failure to read from `reader` is passed to
+/// `on_entry` and ends the stream.
+pub fn parse_desktop_entries_stream<R: Read>(
+    reader: R,
+    mut on_entry: impl FnMut(Result<DesktopEntryType>),
+) {
+    let mut content = String::new();
+    let mut has_main_group = false;
+
+    for line in BufReader::new(reader).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => return on_entry(Err(error.into())),
+        };
+
+        if line == "[Desktop Entry]" {
+            if has_main_group {
+                on_entry(parse_chunk(&content));
+                content.clear();
+            }
+            has_main_group = true;
+        }
+
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    if !content.trim().is_empty() {
+        on_entry(parse_chunk(&content));
+    }
+}
+
+fn parse_chunk(content: &str) -> Result<DesktopEntryType> {
+    parse_desktop_entry_raw_str(content)?.try_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concatenated_entries_are_split_at_each_main_group() {
+        let content = "[Desktop Entry]\nType=Application\nName=First\nExec=first\nActions=new;\n\n\
+            [Desktop Action new]\nName=New Window\nExec=first --new\n\
+            [Desktop Entry]\nType=Link\nName=Second\nURL=https://example.org\n";
+
+        let mut entries = Vec::new();
+        parse_desktop_entries_stream(content.as_bytes(), |entry| entries.push(entry.unwrap()));
+
+        let [
+            DesktopEntryType::Application(first),
+            DesktopEntryType::Link(second),
+        ] = entries.as_slice()
+        else {
+            panic!("unexpected entries: {entries:?}");
+        };
+        assert_eq!(first.name, "First");
+        assert_eq!(first.desktop_actions.len(), 1);
+        assert_eq!(first.desktop_actions[0].name, "New Window");
+        assert_eq!(second.name, "Second");
+    }
+
+    #[test]
+    fn a_broken_entry_does_not_end_the_stream() {
+        let content = "[Desktop Entry]\nName=No Type\n[Desktop Entry]\nType=Directory\nName=Dir\n";
+
+        let mut results = Vec::new();
+        parse_desktop_entries_stream(content.as_bytes(), |entry| results.push(entry.is_ok()));
+        assert_eq!(results, [false, true]);
+    }
+}
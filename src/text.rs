@@ -0,0 +1,281 @@
+// An approximation of extended grapheme clusters that avoids a Unicode segmentation
+// dependency: combining and spacing marks of the common scripts, variation selectors, emoji
+// modifiers and tags attach to the preceding character, a zero width joiner also pulls in the
+// character after it, regional indicators pair up into flags, and Hangul medial vowel and
+// final consonant jamo attach to the syllable before them. Scripts and rules beyond these,
+// such as Indic conjuncts formed with a virama, are not handled.
+fn is_extending(c: char) -> bool {
+    is_combining_mark(c)
+        || matches!(
+            c as u32,
+            0x1160..=0x11FF
+                | 0xD7B0..=0xD7C6
+                | 0xD7CB..=0xD7FB
+                | 0x200C..=0x200D
+                | 0x1F3FB..=0x1F3FF
+                | 0xE0020..=0xE007F
+        )
+}
+
+// The characters of general category Mn, Mc or Me in the blocks of the common scripts, taken
+// from the Unicode 14.0 character database: combining diacritics, Cyrillic, Hebrew, Arabic,
+// the Indic blocks from Devanagari to Sinhala, Thai, Lao, Tibetan, Myanmar and Khmer, and the
+// combining and variation selector blocks.
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F
+            | 0x0483..=0x0489
+            | 0x0591..=0x05BD
+            | 0x05BF
+            | 0x05C1..=0x05C2
+            | 0x05C4..=0x05C5
+            | 0x05C7
+            | 0x0610..=0x061A
+            | 0x064B..=0x065F
+            | 0x0670
+            | 0x06D6..=0x06DC
+            | 0x06DF..=0x06E4
+            | 0x06E7..=0x06E8
+            | 0x06EA..=0x06ED
+            | 0x0900..=0x0903
+            | 0x093A..=0x093C
+            | 0x093E..=0x094F
+            | 0x0951..=0x0957
+            | 0x0962..=0x0963
+            | 0x0981..=0x0983
+            | 0x09BC
+            | 0x09BE..=0x09C4
+            | 0x09C7..=0x09C8
+            | 0x09CB..=0x09CD
+            | 0x09D7
+            | 0x09E2..=0x09E3
+            | 0x09FE
+            | 0x0A01..=0x0A03
+            | 0x0A3C
+            | 0x0A3E..=0x0A42
+            | 0x0A47..=0x0A48
+            | 0x0A4B..=0x0A4D
+            | 0x0A51
+            | 0x0A70..=0x0A71
+            | 0x0A75
+            | 0x0A81..=0x0A83
+            | 0x0ABC
+            | 0x0ABE..=0x0AC5
+            | 0x0AC7..=0x0AC9
+            | 0x0ACB..=0x0ACD
+            | 0x0AE2..=0x0AE3
+            | 0x0AFA..=0x0AFF
+            | 0x0B01..=0x0B03
+            | 0x0B3C
+            | 0x0B3E..=0x0B44
+            | 0x0B47..=0x0B48
+            | 0x0B4B..=0x0B4D
+            | 0x0B55..=0x0B57
+            | 0x0B62..=0x0B63
+            | 0x0B82
+            | 0x0BBE..=0x0BC2
+            | 0x0BC6..=0x0BC8
+            | 0x0BCA..=0x0BCD
+            | 0x0BD7
+            | 0x0C00..=0x0C04
+            | 0x0C3C
+            | 0x0C3E..=0x0C44
+            | 0x0C46..=0x0C48
+            | 0x0C4A..=0x0C4D
+            | 0x0C55..=0x0C56
+            | 0x0C62..=0x0C63
+            | 0x0C81..=0x0C83
+            | 0x0CBC
+            | 0x0CBE..=0x0CC4
+            | 0x0CC6..=0x0CC8
+            | 0x0CCA..=0x0CCD
+            | 0x0CD5..=0x0CD6
+            | 0x0CE2..=0x0CE3
+            | 0x0D00..=0x0D03
+            | 0x0D3B..=0x0D3C
+            | 0x0D3E..=0x0D44
+            | 0x0D46..=0x0D48
+            | 0x0D4A..=0x0D4D
+            | 0x0D57
+            | 0x0D62..=0x0D63
+            | 0x0D81..=0x0D83
+            | 0x0DCA
+            | 0x0DCF..=0x0DD4
+            | 0x0DD6
+            | 0x0DD8..=0x0DDF
+            | 0x0DF2..=0x0DF3
+            | 0x0E31
+            | 0x0E34..=0x0E3A
+            | 0x0E47..=0x0E4E
+            | 0x0EB1
+            | 0x0EB4..=0x0EBC
+            | 0x0EC8..=0x0ECD
+            | 0x0F18..=0x0F19
+            | 0x0F35
+            | 0x0F37
+            | 0x0F39
+            | 0x0F3E..=0x0F3F
+            | 0x0F71..=0x0F84
+            | 0x0F86..=0x0F87
+            | 0x0F8D..=0x0F97
+            | 0x0F99..=0x0FBC
+            | 0x0FC6
+            | 0x102B..=0x103E
+            | 0x1056..=0x1059
+            | 0x105E..=0x1060
+            | 0x1062..=0x1064
+            | 0x1067..=0x106D
+            | 0x1071..=0x1074
+            | 0x1082..=0x108D
+            | 0x108F
+            | 0x109A..=0x109D
+            | 0x17B4..=0x17D3
+            | 0x17DD
+            | 0x1AB0..=0x1ACE
+            | 0x1DC0..=0x1DFF
+            | 0x20D0..=0x20F0
+            | 0x3099..=0x309A
+            | 0xFE00..=0xFE0F
+            | 0xFE20..=0xFE2F
+            | 0xE0100..=0xE01EF
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// Splits `text` into user-perceived characters, approximating extended grapheme clusters as
+/// described for [`is_extending`].
+pub(crate) fn graphemes(text: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, first)) = chars.next() {
+        let mut previous = first;
+        let mut regional_pair = is_regional_indicator(first);
+
+        while let Some(&(_, next)) = chars.peek() {
+            let joins = is_extending(next)
+                || previous == '\u{200D}'
+                || (regional_pair && is_regional_indicator(next));
+            if !joins {
+                break;
+            }
+            regional_pair = false;
+            previous = next;
+            chars.next();
+        }
+
+        let end = chars.peek().map_or(text.len(), |&(index, _)| index);
+        clusters.push(&text[start..end]);
+    }
+
+    clusters
+}
+
+/// Shortens `text` to at most `max_len` graphemes, as approximated by [`graphemes`], replacing
+/// the cut-off part with `…`.
+pub(crate) fn truncate_graphemes(text: &str, max_len: usize) -> String {
+    let clusters = graphemes(text);
+    if clusters.len() <= max_len {
+        return text.to_string();
+    }
+    if max_len == 0 {
+        return String::new();
+    }
+
+    let mut truncated = clusters[..max_len - 1].concat().trim_end().to_string();
+    truncated.push('…');
+    truncated
+}
+
+/// The Pango style tags that show up in non-conforming files. Only these are stripped, and only
+/// in matching pairs, so that text which merely contains `<` is left alone.
+const MARKUP_TAGS: [&str; 9] = ["b", "i", "u", "s", "tt", "big", "small", "sub", "sup"];
+
+/// Removes the [`MARKUP_TAGS`] from `text` wherever an opening tag has a closing tag after it.
+pub(crate) fn strip_markup(text: &str) -> String {
+    let mut text = text.to_string();
+    for tag in MARKUP_TAGS {
+        let (open, close) = (format!("<{tag}>"), format!("</{tag}>"));
+        while let Some(start) = text.find(&open)
+            && let Some(end) = text[start..].find(&close).map(|end| start + end)
+        {
+            text.replace_range(end..end + close.len(), "");
+            text.replace_range(start..start + open.len(), "");
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{graphemes, truncate_graphemes};
+
+    #[test]
+    fn short_text_is_unchanged() {
+        assert_eq!(truncate_graphemes("Files", 5), "Files");
+        assert_eq!(truncate_graphemes("日本語", 3), "日本語");
+    }
+
+    #[test]
+    fn multibyte_characters_are_cut_whole() {
+        assert_eq!(truncate_graphemes("日本語のアプリ", 4), "日本語…");
+        assert_eq!(truncate_graphemes("Ångström", 3), "Ån…");
+    }
+
+    #[test]
+    fn combining_marks_stay_with_their_base() {
+        assert_eq!(graphemes("e\u{301}t\u{e9}"), ["e\u{301}", "t", "\u{e9}"]);
+        assert_eq!(
+            truncate_graphemes("e\u{301}e\u{301}e\u{301}", 2),
+            "e\u{301}…"
+        );
+    }
+
+    #[test]
+    fn thai_and_indic_vowel_signs_stay_with_their_base() {
+        assert_eq!(graphemes("สวัสดี"), ["ส", "วั", "ส", "ดี"]);
+        assert_eq!(truncate_graphemes("สวัสดี", 3), "สวั…");
+        assert_eq!(graphemes("हिंदी"), ["हिं", "दी"]);
+    }
+
+    #[test]
+    fn indic_letters_at_mark_offsets_stand_alone() {
+        // Malayalam chillu and dot reph, Bengali anji and the Kannada spacing candrabindu are
+        // letters, although marks sit at the same offsets in the other Indic blocks.
+        for letter in ['\u{d54}', '\u{d4e}', '\u{980}', '\u{c80}'] {
+            let text = format!("\u{d15}{letter}");
+            assert_eq!(graphemes(&text).len(), 2, "{letter:?}");
+        }
+        assert_eq!(
+            graphemes("\u{d15}\u{d3e}\u{d15}"),
+            ["\u{d15}\u{d3e}", "\u{d15}"]
+        );
+    }
+
+    #[test]
+    fn hangul_jamo_form_one_syllable() {
+        assert_eq!(
+            graphemes("\u{1112}\u{1161}\u{11ab}a"),
+            ["\u{1112}\u{1161}\u{11ab}", "a"]
+        );
+    }
+
+    #[test]
+    fn emoji_sequences_and_flags_stay_whole() {
+        let family = "👩\u{200d}👩\u{200d}👧";
+        assert_eq!(
+            graphemes(&format!("{family}🇩🇪🇫🇷👍🏽")),
+            [family, "🇩🇪", "🇫🇷", "👍🏽"]
+        );
+    }
+
+    #[test]
+    fn tiny_limits() {
+        assert_eq!(truncate_graphemes("abc", 1), "…");
+        assert_eq!(truncate_graphemes("abc", 0), "");
+    }
+}
@@ -0,0 +1,587 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::path::Path;
+
+use crate::categories::is_known_category;
+use crate::escape::split_list;
+use crate::icon::is_misplaced_icon_path;
+use crate::mime::is_valid_mime_type;
+use crate::text::graphemes;
+use crate::{ParseOptions, RawDesktopEntry, RawDesktopFile, Result, SpecVersion, parse_exec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(unused)]
+pub enum Severity {
+    /// The file violates the specification.
+    Error,
+    /// The file is valid but likely not what the author intended.
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub group: String,
+    pub key: Option<String>,
+    /// The 1-based line the issue was found on, when it is tied to one.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+const MAIN_GROUP: &str = "Desktop Entry";
+
+const APPLICATION_ONLY_KEYS: &[&str] = &[
+    "TryExec",
+    "Exec",
+    "Path",
+    "Terminal",
+    "Actions",
+    "MimeType",
+    "Categories",
+    "Implements",
+    "Keywords",
+    "StartupNotify",
+    "StartupWMClass",
+    "PrefersNonDefaultGPU",
+    "SingleMainWindow",
+    "DBusActivatable",
+];
+
+/// Keys added after the first version of the spec, with the version that introduced them.
+const KEY_VERSIONS: &[(&str, SpecVersion)] = &[
+    ("DBusActivatable", SpecVersion { major: 1, minor: 1 }),
+    ("Implements", SpecVersion { major: 1, minor: 1 }),
+    ("PrefersNonDefaultGPU", SpecVersion { major: 1, minor: 4 }),
+    ("SingleMainWindow", SpecVersion { major: 1, minor: 5 }),
+];
+
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct ValidationOptions {
+    /// Warn about `Comment` values longer than this many graphemes, which tend to break tooltip
+    /// layouts. `None` disables the check. Defaults to 120.
+    pub max_comment_length: Option<usize>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            max_comment_length: Some(120),
+        }
+    }
+}
+
+/// Checks a raw entry against the specification, returning every problem found rather than
+/// stopping at the first one.
+pub fn validate(raw: &RawDesktopEntry) -> Vec<ValidationIssue> {
+    validate_with(raw, &ValidationOptions::default())
+}
+
+/// [`validate`] with `options`.
+pub fn validate_with(raw: &RawDesktopEntry, options: &ValidationOptions) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let Some(group) = raw.get(MAIN_GROUP) else {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            group: MAIN_GROUP.to_string(),
+            key: None,
+            line: None,
+            message: "Desktop entry group missing".to_string(),
+        });
+        return issues;
+    };
+
+    require_key(group, "Name", &mut issues);
+    check_key_versions(group, &mut issues);
+    check_show_in_overlap(group, &mut issues);
+    check_icon(group, &mut issues);
+    if let Some(max_length) = options.max_comment_length {
+        check_comment_length(group, max_length, &mut issues);
+    }
+
+    match group.get("Type").map(String::as_str) {
+        None => require_key(group, "Type", &mut issues),
+        Some("Application") => {
+            check_mime_types(group, &mut issues);
+            check_categories(group, &mut issues);
+            check_dbus_exec(group, &mut issues);
+            check_mime_exec(group, &mut issues);
+        }
+        Some(entry_type @ ("Link" | "Directory")) => {
+            if entry_type == "Link" {
+                require_key(group, "URL", &mut issues);
+            }
+            check_application_only_keys(group, entry_type, &mut issues);
+        }
+        Some(unknown) => issues.push(ValidationIssue {
+            severity: Severity::Error,
+            group: MAIN_GROUP.to_string(),
+            key: Some("Type".to_string()),
+            line: None,
+            message: format!("Unknown entry type {unknown}"),
+        }),
+    }
+
+    issues
+}
+
+/// Validates the contents of a file. Besides everything [`validate`] checks, this includes
+/// problems only visible in the text itself, like a key repeated within a group, and reports an
+/// [`Severity::Error`] if the content can't be parsed at all. The other checks see the last
+/// value of a repeated key.
+pub fn validate_str(content: &str) -> Vec<ValidationIssue> {
+    let mut issues = check_lines(content);
+
+    match RawDesktopFile::parse_with(content, &ParseOptions::keeping_last_value()) {
+        Ok((file, _)) => {
+            for group in file.duplicate_groups() {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    group: group.clone(),
+                    key: None,
+                    line: None,
+                    message: format!("Group [{group}] is declared more than once"),
+                });
+            }
+            issues.extend(validate(file.groups()));
+        }
+        Err(error) => issues.push(ValidationIssue {
+            severity: Severity::Error,
+            group: String::new(),
+            key: None,
+            line: None,
+            message: error.to_string(),
+        }),
+    }
+
+    issues
+}
+
+/// [`validate_str`] for the file at `path`.
+pub fn validate_file<P: AsRef<Path>>(path: P) -> Result<Vec<ValidationIssue>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(validate_str(&content))
+}
+
+fn check_lines(content: &str) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut group = String::new();
+    // The line each key was first seen on, by group and key.
+    let mut first_lines: HashMap<(String, String), usize> = HashMap::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            group = line[1..line.len() - 1].to_string();
+            continue;
+        }
+
+        let key = line.split_once('=').map_or(line, |(key, _)| key);
+        if key.contains('\t') {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                group: group.clone(),
+                key: Some(key.trim().to_string()),
+                line: Some(index + 1),
+                message: "Tab before '=', the key and value are likely separated incorrectly"
+                    .to_string(),
+            });
+        }
+
+        if line.contains('=') {
+            let key = key.trim();
+            let line_number = index + 1;
+            match first_lines.entry((group.clone(), key.to_string())) {
+                Entry::Occupied(first) => issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    group: group.clone(),
+                    key: Some(key.to_string()),
+                    line: Some(line_number),
+                    message: format!("Key '{key}' is repeated, first set on line {}", first.get()),
+                }),
+                Entry::Vacant(vacant) => {
+                    vacant.insert(line_number);
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+fn require_key(group: &HashMap<String, String>, key: &str, issues: &mut Vec<ValidationIssue>) {
+    if !group.contains_key(key) {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            group: MAIN_GROUP.to_string(),
+            key: Some(key.to_string()),
+            line: None,
+            message: format!("Missing required key '{key}'"),
+        });
+    }
+}
+
+fn check_key_versions(group: &HashMap<String, String>, issues: &mut Vec<ValidationIssue>) {
+    let Some(declared) = group.get("Version") else {
+        return;
+    };
+    let Ok(version) = declared.parse::<SpecVersion>() else {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            group: MAIN_GROUP.to_string(),
+            key: Some("Version".to_string()),
+            line: None,
+            message: format!("Invalid version '{declared}'"),
+        });
+        return;
+    };
+
+    for (key, introduced) in KEY_VERSIONS {
+        if *introduced > version && group.contains_key(*key) {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                group: MAIN_GROUP.to_string(),
+                key: Some(key.to_string()),
+                line: None,
+                message: format!(
+                    "Key '{key}' was introduced in version {introduced}, but the entry declares version {version}"
+                ),
+            });
+        }
+    }
+}
+
+fn check_mime_types(group: &HashMap<String, String>, issues: &mut Vec<ValidationIssue>) {
+    let Some(mime_types) = group.get("MimeType") else {
+        return;
+    };
+
+    for mime_type in split_list(mime_types) {
+        if !is_valid_mime_type(&mime_type) {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                group: MAIN_GROUP.to_string(),
+                key: Some("MimeType".to_string()),
+                line: None,
+                message: format!("'{mime_type}' is not a type/subtype MIME type"),
+            });
+        }
+    }
+}
+
+/// An application handling MIME types must be able to take the file, unless it is started over
+/// D-Bus, which passes files by other means.
+fn check_mime_exec(group: &HashMap<String, String>, issues: &mut Vec<ValidationIssue>) {
+    let has_mime_types = group
+        .get("MimeType")
+        .is_some_and(|mime_types| !split_list(mime_types).is_empty());
+    if !has_mime_types || group.get("DBusActivatable").map(String::as_str) == Some("true") {
+        return;
+    }
+    let Some(args) = group.get("Exec").and_then(|exec| parse_exec(exec).ok()) else {
+        return;
+    };
+
+    let takes_files = args.iter().any(|arg| {
+        let mut chars = arg.chars();
+        while let Some(c) = chars.next() {
+            if c == '%' && matches!(chars.next(), Some('f' | 'F' | 'u' | 'U')) {
+                return true;
+            }
+        }
+        false
+    });
+    if !takes_files {
+        issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            group: MAIN_GROUP.to_string(),
+            key: Some("Exec".to_string()),
+            line: None,
+            message: "MimeType is set, but Exec takes no file or URL (%f, %F, %u or %U)"
+                .to_string(),
+        });
+    }
+}
+
+fn check_categories(group: &HashMap<String, String>, issues: &mut Vec<ValidationIssue>) {
+    let Some(categories) = group.get("Categories") else {
+        return;
+    };
+
+    for category in split_list(categories) {
+        if !is_known_category(&category) {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                group: MAIN_GROUP.to_string(),
+                key: Some("Categories".to_string()),
+                line: None,
+                message: format!(
+                    "'{category}' is not a registered category, extensions need an X- prefix"
+                ),
+            });
+        }
+    }
+}
+
+fn check_application_only_keys(
+    group: &HashMap<String, String>,
+    entry_type: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let mut keys: Vec<&String> = group.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        if APPLICATION_ONLY_KEYS.contains(&base_key(key)) {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                group: MAIN_GROUP.to_string(),
+                key: Some(key.clone()),
+                line: None,
+                message: format!(
+                    "Key '{key}' only applies to Application entries and is ignored for {entry_type}"
+                ),
+            });
+        }
+    }
+}
+
+fn check_comment_length(
+    group: &HashMap<String, String>,
+    max_length: usize,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let mut keys: Vec<&String> = group
+        .keys()
+        .filter(|key| base_key(key) == "Comment")
+        .collect();
+    keys.sort();
+
+    for key in keys {
+        let length = graphemes(&group[key]).len();
+        if length > max_length {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                group: MAIN_GROUP.to_string(),
+                key: Some(key.clone()),
+                line: None,
+                message: format!(
+                    "'{key}' is {length} characters long, more than the {max_length} that fit a tooltip"
+                ),
+            });
+        }
+    }
+}
+
+fn check_show_in_overlap(group: &HashMap<String, String>, issues: &mut Vec<ValidationIssue>) {
+    let (Some(only_show_in), Some(not_show_in)) = (group.get("OnlyShowIn"), group.get("NotShowIn"))
+    else {
+        return;
+    };
+    let not_show_in = split_list(not_show_in);
+
+    for desktop in split_list(only_show_in) {
+        if not_show_in.contains(&desktop) {
+            issues.push(ValidationIssue {
+                severity: Severity::Error,
+                group: MAIN_GROUP.to_string(),
+                key: Some("NotShowIn".to_string()),
+                line: None,
+                message: format!("'{desktop}' is listed in both OnlyShowIn and NotShowIn"),
+            });
+        }
+    }
+}
+
+fn check_icon(group: &HashMap<String, String>, issues: &mut Vec<ValidationIssue>) {
+    if let Some(icon) = group.get("Icon")
+        && is_misplaced_icon_path(icon)
+    {
+        issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            group: MAIN_GROUP.to_string(),
+            key: Some("Icon".to_string()),
+            line: None,
+            message: format!(
+                "Icon '{icon}' is neither an absolute path nor an icon name without an extension"
+            ),
+        });
+    }
+}
+
+fn check_dbus_exec(group: &HashMap<String, String>, issues: &mut Vec<ValidationIssue>) {
+    if group.get("DBusActivatable").map(String::as_str) != Some("true") {
+        return;
+    }
+    let Some(program) = group
+        .get("Exec")
+        .and_then(|exec| parse_exec(exec).ok())
+        .and_then(|args| args.into_iter().next())
+    else {
+        return;
+    };
+
+    if !Path::new(&program).is_absolute() {
+        issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            group: MAIN_GROUP.to_string(),
+            key: Some("Exec".to_string()),
+            line: None,
+            message: format!(
+                "DBusActivatable is true, but Exec doesn't name '{program}' by an absolute path"
+            ),
+        });
+    }
+}
+
+/// Strips a locale suffix, so `Keywords[de]` becomes `Keywords`.
+fn base_key(key: &str) -> &str {
+    key.split_once('[').map_or(key, |(base, _)| base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_keys_are_reported_without_hiding_other_issues() {
+        let issues = validate_str(
+            "[Desktop Entry]\nType=Application\nName=A\nName=B\nExec=a\nName=C\nExec=b\n\
+            MimeType=not-a-type;\n",
+        );
+        let repeated: Vec<_> = issues
+            .iter()
+            .filter(|issue| issue.message.contains("repeated"))
+            .map(|issue| (issue.key.as_deref().unwrap(), issue.line.unwrap()))
+            .collect();
+        assert_eq!(repeated, [("Name", 4), ("Name", 6), ("Exec", 7)]);
+        assert!(issues.iter().all(|issue| issue.group == "Desktop Entry"));
+        assert!(
+            issues
+                .iter()
+                .any(|issue| issue.key.as_deref() == Some("MimeType"))
+        );
+    }
+
+    const APPLICATION: &str = "[Desktop Entry]\nType=Application\nName=App\nExec=app %f\n";
+
+    /// The issues of `content`, as `(severity, key, message)`.
+    fn issues(content: &str) -> Vec<(Severity, Option<String>, String)> {
+        validate_str(content)
+            .into_iter()
+            .map(|issue| (issue.severity, issue.key, issue.message))
+            .collect()
+    }
+
+    /// The single issue of `APPLICATION` with `extra` lines appended.
+    fn only_issue(extra: &str) -> (Severity, Option<String>, String) {
+        let mut issues = issues(&format!("{APPLICATION}{extra}"));
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        issues.remove(0)
+    }
+
+    #[test]
+    fn valid_entry_has_no_issues() {
+        assert!(issues(APPLICATION).is_empty());
+    }
+
+    #[test]
+    fn application_only_key_on_a_link() {
+        let found = issues(
+            "[Desktop Entry]\nType=Link\nName=Site\nURL=https://example.org\nTerminal=true\n",
+        );
+        assert_eq!(found.len(), 1, "{found:?}");
+        assert_eq!(found[0].0, Severity::Warning);
+        assert_eq!(found[0].1.as_deref(), Some("Terminal"));
+    }
+
+    #[test]
+    fn key_newer_than_version() {
+        let (severity, key, message) = only_issue("Version=1.4\nSingleMainWindow=true\n");
+        assert_eq!(severity, Severity::Warning);
+        assert_eq!(key.as_deref(), Some("SingleMainWindow"));
+        assert!(message.contains("version 1.5"), "{message}");
+        assert!(
+            issues(&format!(
+                "{APPLICATION}Version=1.5\nSingleMainWindow=true\n"
+            ))
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn malformed_mime_type() {
+        let (severity, key, message) = only_issue("MimeType=text/plain;image;\n");
+        assert_eq!(severity, Severity::Warning);
+        assert_eq!(key.as_deref(), Some("MimeType"));
+        assert!(message.contains("'image'"), "{message}");
+    }
+
+    #[test]
+    fn tab_before_equals() {
+        let issues = validate_str(&format!("{APPLICATION}Comment\t=Text\n"));
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].key.as_deref(), Some("Comment"));
+        assert_eq!(issues[0].line, Some(5));
+    }
+
+    #[test]
+    fn only_show_in_and_not_show_in_overlap() {
+        let (severity, key, message) = only_issue("OnlyShowIn=GNOME;KDE;\nNotShowIn=KDE;\n");
+        assert_eq!(severity, Severity::Error);
+        assert_eq!(key.as_deref(), Some("NotShowIn"));
+        assert!(message.contains("'KDE'"), "{message}");
+    }
+
+    #[test]
+    fn dbus_activatable_with_relative_exec() {
+        let (severity, key, _) = only_issue("DBusActivatable=true\n");
+        assert_eq!(severity, Severity::Warning);
+        assert_eq!(key.as_deref(), Some("Exec"));
+        let absolute = "[Desktop Entry]\nType=Application\nName=App\nExec=/usr/bin/app\n\
+            DBusActivatable=true\n";
+        assert!(issues(absolute).is_empty());
+    }
+
+    #[test]
+    fn long_comment() {
+        let (severity, key, _) = only_issue(&format!("Comment[de]={}\n", "ä".repeat(121)));
+        assert_eq!(severity, Severity::Warning);
+        assert_eq!(key.as_deref(), Some("Comment[de]"));
+        assert!(issues(&format!("{APPLICATION}Comment={}\n", "ä".repeat(120))).is_empty());
+
+        let raw = crate::parse_desktop_entry_raw_str(&format!(
+            "{APPLICATION}Comment={}\n",
+            "a".repeat(121)
+        ))
+        .unwrap();
+        let options = ValidationOptions {
+            max_comment_length: None,
+        };
+        assert!(validate_with(&raw, &options).is_empty());
+    }
+
+    #[test]
+    fn icon_with_an_extension() {
+        let (severity, key, _) = only_issue("Icon=foo.png\n");
+        assert_eq!(severity, Severity::Warning);
+        assert_eq!(key.as_deref(), Some("Icon"));
+        assert!(issues(&format!("{APPLICATION}Icon=/usr/share/foo.png\n")).is_empty());
+        assert!(issues(&format!("{APPLICATION}Icon=foo\n")).is_empty());
+    }
+
+    #[test]
+    fn mime_type_without_a_file_argument() {
+        let content =
+            "[Desktop Entry]\nType=Application\nName=App\nExec=app\nMimeType=text/plain;\n";
+        let found = issues(content);
+        assert_eq!(found.len(), 1, "{found:?}");
+        assert_eq!(found[0].1.as_deref(), Some("Exec"));
+        let dbus = content.replace("Exec=app", "Exec=/usr/bin/app");
+        assert!(issues(&format!("{dbus}DBusActivatable=true\n")).is_empty());
+    }
+}
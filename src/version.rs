@@ -0,0 +1,63 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    ApplicationDesktopEntry, DesktopEntryType, DirectoryDesktopEntry, Error, LinkDesktopEntry,
+};
+
+/// A version of the desktop entry specification, as declared by the `Version` key. Versions
+/// order by major, then minor version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(unused)]
+pub struct SpecVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl FromStr for SpecVersion {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::FormatError(format!("Invalid spec version '{value}'"));
+        let (major, minor) = value.split_once('.').ok_or_else(invalid)?;
+        Ok(SpecVersion {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+macro_rules! impl_spec_version {
+    ($($entry:ty),*) => {$(
+        impl $entry {
+            /// The parsed `Version`, or `None` if it is absent or malformed.
+            pub fn spec_version(&self) -> Option<SpecVersion> {
+                self.version.as_deref()?.parse().ok()
+            }
+        }
+    )*};
+}
+
+impl_spec_version!(
+    ApplicationDesktopEntry,
+    LinkDesktopEntry,
+    DirectoryDesktopEntry
+);
+
+impl DesktopEntryType {
+    /// The parsed `Version` of the entry, whatever its type. As `None` orders before any
+    /// version, `entry.spec_version() >= Some(version)` is false for entries without one.
+    pub fn spec_version(&self) -> Option<SpecVersion> {
+        match self {
+            DesktopEntryType::Application(entry) => entry.spec_version(),
+            DesktopEntryType::Link(entry) => entry.spec_version(),
+            DesktopEntryType::Directory(entry) => entry.spec_version(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::env;
+
+use crate::exec::find_executable;
+use crate::{ApplicationDesktopEntry, DirectoryDesktopEntry};
+
+/// The desktop environments named in `$XDG_CURRENT_DESKTOP`, for use with `OnlyShowIn` and
+/// `NotShowIn`.
+pub fn current_desktops() -> Vec<String> {
+    env::var("XDG_CURRENT_DESKTOP")
+        .map(|value| {
+            value
+                .split(':')
+                .filter(|desktop| !desktop.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl ApplicationDesktopEntry {
+    /// Whether the entry should be shown in menus on the given desktops (as listed in
+    /// [`current_desktops`]). It is hidden when `Hidden` or `NoDisplay` are set, when
+    /// `OnlyShowIn` names none of `desktops` or `NotShowIn` names one of them, or when the
+    /// `TryExec` program can't be found.
+    pub fn is_visible(&self, desktops: &[&str]) -> bool {
+        if self.hidden == Some(true) || self.no_display == Some(true) {
+            return false;
+        }
+
+        let names_desktop = |shown_in: &Option<Vec<String>>| {
+            shown_in.as_ref().map(|shown_in| {
+                desktops
+                    .iter()
+                    .any(|desktop| shown_in.iter().any(|shown| shown == desktop))
+            })
+        };
+        if names_desktop(&self.only_show_in) == Some(false)
+            || names_desktop(&self.not_show_in) == Some(true)
+        {
+            return false;
+        }
+
+        match &self.try_exec {
+            Some(try_exec) => find_executable(try_exec).is_some(),
+            None => true,
+        }
+    }
+
+    /// Whether the entry belongs in a terminal context rather than a GUI menu: it sets
+    /// `Terminal=true`, or lists the `ConsoleOnly` or `TerminalEmulator` category. Other hints,
+    /// such as a missing `Icon`, are deliberately not taken into account.
+    pub fn is_terminal_application(&self) -> bool {
+        self.terminal == Some(true)
+            || self
+                .categories
+                .iter()
+                .flatten()
+                .any(|category| category == "ConsoleOnly" || category == "TerminalEmulator")
+    }
+
+    /// Whether the entry is kept out of a menu built from `dir_context`, the `.directory` entry
+    /// of the menu it appears in: either of them setting `NoDisplay=true` hides it.
+    pub fn effective_no_display(&self, dir_context: &DirectoryDesktopEntry) -> bool {
+        self.no_display_or_default() || dir_context.no_display_or_default()
+    }
+}
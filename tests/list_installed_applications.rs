@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use xdg_desktop_entries::{AppSummary, list_installed_applications_in};
+
+fn fixture_dirs() -> Vec<PathBuf> {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/shadowing");
+    vec![root.join("user"), root.join("system")]
+}
+
+fn summary(id: &str, name: &str, icon: Option<&str>, exec: &str) -> AppSummary {
+    AppSummary {
+        id: id.to_string(),
+        name: name.to_string(),
+        icon: icon.map(str::to_string),
+        exec: Some(exec.to_string()),
+    }
+}
+
+#[test]
+fn earlier_directories_shadow_later_ones_and_hidden_entries_are_left_out() {
+    let applications = list_installed_applications_in(&fixture_dirs()).unwrap();
+
+    // `user/hidden.desktop` is hidden and shadows the visible `system/hidden.desktop`, and
+    // `user/nodisplay.desktop` sets `NoDisplay`. The broken file, the Link entry and the README
+    // are skipped.
+    assert_eq!(
+        applications,
+        [
+            summary("editor.desktop", "User Editor", None, "editor"),
+            summary("kde-konsole.desktop", "Konsole", None, "konsole"),
+            summary("viewer.desktop", "Viewer", Some("viewer"), "viewer %f"),
+        ]
+    );
+}
+
+#[test]
+fn missing_directories_are_skipped() {
+    let mut dirs = fixture_dirs();
+    dirs.insert(
+        0,
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/missing"),
+    );
+    assert_eq!(list_installed_applications_in(&dirs).unwrap().len(), 3);
+}